@@ -0,0 +1,166 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use cosmwasm_std::{
+    Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, SubMsg, Uint128,
+};
+
+use cw20_base::allowances::{
+    execute_decrease_allowance, execute_increase_allowance, execute_send_from,
+    execute_transfer_from,
+};
+use cw20_base::contract::{
+    execute_burn, execute_mint, execute_send, execute_transfer, execute_update_marketing,
+    execute_upload_logo,
+};
+use cw20_base::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use cw20_base::state::TOKEN_INFO;
+use reward_pool::reward_pool::ReceiptTransferHookMsg;
+
+use crate::error::ContractError;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.mint.is_none() {
+        return Err(ContractError::MinterRequired {});
+    }
+
+    Ok(cw20_base::contract::instantiate(deps, env, info, msg)?)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let pool = query_pool(deps.as_ref())?;
+    match msg {
+        ExecuteMsg::Transfer { recipient, amount } => {
+            assert_not_pool(&pool, &recipient)?;
+            let hook_msg = transfer_hook_msg(&pool, info.sender.as_str(), &recipient, amount)?;
+            let res = execute_transfer(deps, env, info, recipient, amount)?;
+            Ok(prepend_hook(res, hook_msg))
+        }
+        ExecuteMsg::Send {
+            contract,
+            amount,
+            msg,
+        } => {
+            let hook_msg = transfer_hook_msg(&pool, info.sender.as_str(), &contract, amount)?;
+            let res = execute_send(deps, env, info, contract, amount, msg)?;
+            Ok(prepend_hook(res, hook_msg))
+        }
+        ExecuteMsg::TransferFrom {
+            owner,
+            recipient,
+            amount,
+        } => {
+            assert_not_pool(&pool, &recipient)?;
+            let hook_msg = transfer_hook_msg(&pool, &owner, &recipient, amount)?;
+            let res = execute_transfer_from(deps, env, info, owner, recipient, amount)?;
+            Ok(prepend_hook(res, hook_msg))
+        }
+        ExecuteMsg::SendFrom {
+            owner,
+            contract,
+            amount,
+            msg,
+        } => {
+            assert_not_pool(&pool, &contract)?;
+            let hook_msg = transfer_hook_msg(&pool, &owner, &contract, amount)?;
+            let res = execute_send_from(deps, env, info, owner, contract, amount, msg)?;
+            Ok(prepend_hook(res, hook_msg))
+        }
+        ExecuteMsg::Burn { amount } => {
+            if info.sender != pool {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            Ok(execute_burn(deps, env, info, amount)?)
+        }
+        ExecuteMsg::BurnFrom { .. } => Err(ContractError::Unauthorized {}),
+        ExecuteMsg::Mint { recipient, amount } => {
+            Ok(execute_mint(deps, env, info, recipient, amount)?)
+        }
+        ExecuteMsg::IncreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => Ok(execute_increase_allowance(
+            deps, env, info, spender, amount, expires,
+        )?),
+        ExecuteMsg::DecreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => Ok(execute_decrease_allowance(
+            deps, env, info, spender, amount, expires,
+        )?),
+        ExecuteMsg::UpdateMarketing {
+            project,
+            description,
+            marketing,
+        } => Ok(execute_update_marketing(
+            deps,
+            env,
+            info,
+            project,
+            description,
+            marketing,
+        )?),
+        ExecuteMsg::UploadLogo(logo) => Ok(execute_upload_logo(deps, env, info, logo)?),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    cw20_base::contract::query(deps, env, msg)
+}
+
+/// The minter of the receipts is the reward pool
+fn query_pool(deps: Deps) -> Result<Addr, ContractError> {
+    TOKEN_INFO
+        .load(deps.storage)?
+        .mint
+        .map(|mint| mint.minter)
+        .ok_or(ContractError::MinterRequired {})
+}
+
+/// Receipts are only returned to the pool with `Send` by their owner,
+/// which the pool receives as a withdrawal of the owner
+fn assert_not_pool(pool: &Addr, recipient: &str) -> Result<(), ContractError> {
+    if pool.as_str() == recipient {
+        return Err(ContractError::InvalidReturn {});
+    }
+
+    Ok(())
+}
+
+fn transfer_hook_msg(
+    pool: &Addr,
+    sender: &str,
+    recipient: &str,
+    amount: Uint128,
+) -> StdResult<SubMsg> {
+    Ok(SubMsg::new(
+        ReceiptTransferHookMsg {
+            sender: sender.to_string(),
+            recipient: recipient.to_string(),
+            amount,
+        }
+        .into_cosmos_msg(pool.to_string())?,
+    ))
+}
+
+/// The pool moves the stake before the recipient of a send is called
+fn prepend_hook(mut res: Response, hook_msg: SubMsg) -> Response {
+    res.messages.insert(0, hook_msg);
+    res
+}
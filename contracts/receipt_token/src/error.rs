@@ -0,0 +1,20 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Base(#[from] cw20_base::ContractError),
+
+    #[error("unauthorized")]
+    Unauthorized {},
+
+    #[error("the reward pool has to be set as minter")]
+    MinterRequired {},
+
+    #[error("receipts can only be returned to the reward pool with send")]
+    InvalidReturn {},
+}
@@ -0,0 +1,67 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{from_binary, to_binary, Uint128};
+
+use cw20::{Cw20ExecuteMsg, TokenInfoResponse};
+use cw20_base::msg::QueryMsg;
+
+use crate::{
+    contract::{execute, query},
+    error::ContractError,
+    tests::test_utils::{instantiate_receipt_token, POOL},
+};
+
+#[test]
+fn burn_receipts_returned_to_pool() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_receipt_token(&mut deps);
+
+    let msg = Cw20ExecuteMsg::Send {
+        contract: POOL.to_string(),
+        amount: Uint128::from(40u128),
+        msg: to_binary("withdraw").unwrap(),
+    };
+
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = Cw20ExecuteMsg::Burn {
+        amount: Uint128::from(40u128),
+    };
+
+    execute(deps.as_mut(), mock_env(), mock_info(POOL, &[]), msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap();
+
+    let token_info_res: TokenInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(token_info_res.total_supply, Uint128::from(60u128));
+}
+
+#[test]
+fn fails_if_burner_is_not_pool() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_receipt_token(&mut deps);
+
+    let msg = Cw20ExecuteMsg::IncreaseAllowance {
+        spender: POOL.to_string(),
+        amount: Uint128::from(40u128),
+        expires: None,
+    };
+
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = Cw20ExecuteMsg::Burn {
+        amount: Uint128::from(40u128),
+    };
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let msg = Cw20ExecuteMsg::BurnFrom {
+        owner: "addr".to_string(),
+        amount: Uint128::from(40u128),
+    };
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info(POOL, &[]), msg).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+}
@@ -0,0 +1,47 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{from_binary, Uint128};
+
+use cw20::{MinterResponse, TokenInfoResponse};
+use cw20_base::msg::{InstantiateMsg, QueryMsg};
+
+use crate::{
+    contract::{instantiate, query},
+    error::ContractError,
+    tests::test_utils::{instantiate_receipt_token, POOL},
+};
+
+#[test]
+fn instantiate_with_pool_as_minter() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_receipt_token(&mut deps);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Minter {}).unwrap();
+
+    let minter_res: Option<MinterResponse> = from_binary(&res).unwrap();
+    assert_eq!(minter_res.unwrap().minter, POOL.to_string());
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap();
+
+    let token_info_res: TokenInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(token_info_res.total_supply, Uint128::from(100u128));
+}
+
+#[test]
+fn fails_if_minter_is_not_set() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        name: "Staked Token".to_string(),
+        symbol: "sTKN".to_string(),
+        decimals: 6,
+        initial_balances: vec![],
+        mint: None,
+        marketing: None,
+    };
+
+    let info = mock_info(POOL, &[]);
+
+    let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::MinterRequired {});
+}
@@ -0,0 +1,4 @@
+pub mod burn_test;
+pub mod instantiate_test;
+pub mod test_utils;
+pub mod transfer_test;
@@ -0,0 +1,44 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{to_binary, CosmosMsg, OwnedDeps, SubMsg, Uint128, WasmMsg};
+
+use cw20::{Cw20Coin, MinterResponse};
+use cw20_base::msg::InstantiateMsg;
+use reward_pool::reward_pool::{ExecuteMsg, ReceiptTransferHookMsg};
+
+use crate::contract::instantiate;
+
+pub const POOL: &str = "reward_pool";
+
+pub fn instantiate_receipt_token(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>) {
+    let msg = InstantiateMsg {
+        name: "Staked Token".to_string(),
+        symbol: "sTKN".to_string(),
+        decimals: 6,
+        initial_balances: vec![Cw20Coin {
+            address: "addr".to_string(),
+            amount: Uint128::from(100u128),
+        }],
+        mint: Some(MinterResponse {
+            minter: POOL.to_string(),
+            cap: None,
+        }),
+        marketing: None,
+    };
+
+    let info = mock_info(POOL, &[]);
+
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+pub fn hook_msg(sender: &str, recipient: &str, amount: u128) -> SubMsg {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: POOL.to_string(),
+        msg: to_binary(&ExecuteMsg::ReceiptTransferHook(ReceiptTransferHookMsg {
+            sender: sender.to_string(),
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+        }))
+        .unwrap(),
+        funds: vec![],
+    }))
+}
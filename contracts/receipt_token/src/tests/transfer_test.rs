@@ -0,0 +1,140 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{from_binary, to_binary, Binary, Uint128};
+
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw20_base::msg::QueryMsg;
+
+use crate::{
+    contract::{execute, query},
+    error::ContractError,
+    tests::test_utils::{hook_msg, instantiate_receipt_token, POOL},
+};
+
+#[test]
+fn notify_pool_on_transfer() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_receipt_token(&mut deps);
+
+    let msg = Cw20ExecuteMsg::Transfer {
+        recipient: "holder".to_string(),
+        amount: Uint128::from(40u128),
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(res.messages, vec![hook_msg("addr", "holder", 40)]);
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Balance {
+            address: "holder".to_string(),
+        },
+    )
+    .unwrap();
+
+    let balance_res: BalanceResponse = from_binary(&res).unwrap();
+    assert_eq!(balance_res.balance, Uint128::from(40u128));
+}
+
+#[test]
+fn notify_pool_before_send() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_receipt_token(&mut deps);
+
+    let msg = Cw20ExecuteMsg::Send {
+        contract: POOL.to_string(),
+        amount: Uint128::from(40u128),
+        msg: to_binary("withdraw").unwrap(),
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(res.messages[0], hook_msg("addr", POOL, 40));
+    assert_eq!(
+        res.messages[1].msg,
+        Cw20ReceiveMsg {
+            sender: "addr".to_string(),
+            amount: Uint128::from(40u128),
+            msg: to_binary("withdraw").unwrap(),
+        }
+        .into_cosmos_msg(POOL.to_string())
+        .unwrap()
+    );
+}
+
+#[test]
+fn notify_pool_on_transfer_from() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_receipt_token(&mut deps);
+
+    let msg = Cw20ExecuteMsg::IncreaseAllowance {
+        spender: "operator".to_string(),
+        amount: Uint128::from(40u128),
+        expires: None,
+    };
+
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    let msg = Cw20ExecuteMsg::TransferFrom {
+        owner: "addr".to_string(),
+        recipient: "holder".to_string(),
+        amount: Uint128::from(40u128),
+    };
+
+    let info = mock_info("operator", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(res.messages, vec![hook_msg("addr", "holder", 40)]);
+}
+
+#[test]
+fn fails_to_return_receipts_without_send() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_receipt_token(&mut deps);
+
+    let msg = Cw20ExecuteMsg::IncreaseAllowance {
+        spender: "operator".to_string(),
+        amount: Uint128::from(40u128),
+        expires: None,
+    };
+
+    execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+
+    for (sender, msg) in [
+        (
+            "addr",
+            Cw20ExecuteMsg::Transfer {
+                recipient: POOL.to_string(),
+                amount: Uint128::from(40u128),
+            },
+        ),
+        (
+            "operator",
+            Cw20ExecuteMsg::TransferFrom {
+                owner: "addr".to_string(),
+                recipient: POOL.to_string(),
+                amount: Uint128::from(40u128),
+            },
+        ),
+        (
+            "operator",
+            Cw20ExecuteMsg::SendFrom {
+                owner: "addr".to_string(),
+                contract: POOL.to_string(),
+                amount: Uint128::from(40u128),
+                msg: Binary::default(),
+            },
+        ),
+    ] {
+        let res = execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidReturn {});
+    }
+}
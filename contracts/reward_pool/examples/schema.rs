@@ -1,22 +1,43 @@
-use std::env::current_dir;
-use std::fs::create_dir_all;
-
-use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
-
-use reward_pool::reward_pool::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, State, UserInfoResponse,
-};
-
-fn main() {
-    let mut out_dir = current_dir().unwrap();
-    out_dir.push("schema");
-    create_dir_all(&out_dir).unwrap();
-    remove_schemas(&out_dir).unwrap();
-
-    export_schema(&schema_for!(InstantiateMsg), &out_dir);
-    export_schema(&schema_for!(ExecuteMsg), &out_dir);
-    export_schema(&schema_for!(QueryMsg), &out_dir);
-    export_schema(&schema_for!(ConfigResponse), &out_dir);
-    export_schema(&schema_for!(State), &out_dir);
-    export_schema(&schema_for!(UserInfoResponse), &out_dir);
-}
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+
+use reward_pool::reward_pool::{
+    AllUsersResponse, ApprovalsResponse, ClaimableRewardsResponse, ConfigResponse, ExecuteMsg,
+    FundHistoryResponse, FundersResponse, HooksResponse, InstantiateMsg, LocksResponse,
+    MemberListResponse, MemberResponse, QueryMsg, ReceiptTokenInstantiateMsg,
+    RewardSchedulesResponse, SolvencyResponse, StakeAtHeightResponse, StakeChangedExecuteMsg,
+    State, SwapPairsResponse, TotalWeightResponse, UnbondsResponse, UserInfoResponse,
+};
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(State), &out_dir);
+    export_schema(&schema_for!(UserInfoResponse), &out_dir);
+    export_schema(&schema_for!(ClaimableRewardsResponse), &out_dir);
+    export_schema(&schema_for!(AllUsersResponse), &out_dir);
+    export_schema(&schema_for!(StakeAtHeightResponse), &out_dir);
+    export_schema(&schema_for!(RewardSchedulesResponse), &out_dir);
+    export_schema(&schema_for!(SolvencyResponse), &out_dir);
+    export_schema(&schema_for!(LocksResponse), &out_dir);
+    export_schema(&schema_for!(UnbondsResponse), &out_dir);
+    export_schema(&schema_for!(FundersResponse), &out_dir);
+    export_schema(&schema_for!(FundHistoryResponse), &out_dir);
+    export_schema(&schema_for!(ApprovalsResponse), &out_dir);
+    export_schema(&schema_for!(SwapPairsResponse), &out_dir);
+    export_schema(&schema_for!(HooksResponse), &out_dir);
+    export_schema(&schema_for!(StakeChangedExecuteMsg), &out_dir);
+    export_schema(&schema_for!(ReceiptTokenInstantiateMsg), &out_dir);
+    export_schema(&schema_for!(MemberResponse), &out_dir);
+    export_schema(&schema_for!(TotalWeightResponse), &out_dir);
+    export_schema(&schema_for!(MemberListResponse), &out_dir);
+}
@@ -1,97 +1,429 @@
-#[cfg(not(feature = "library"))]
-use cosmwasm_std::entry_point;
-
-use cosmwasm_std::{
-    from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
-};
-
-use cw20::Cw20ReceiveMsg;
-use reward_pool::reward_pool::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, State,
-};
-use terraswap::asset::AssetInfoRaw;
-
-use crate::{
-    execute::{claim, deposit, fund, update_config, withdraw},
-    query::{query_config, query_state, query_user_info},
-    state::{Config, CONFIGURATION, STATE},
-    utils::get_received_native_fund,
-};
-
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn instantiate(
-    deps: DepsMut,
-    _env: Env,
-    _info: MessageInfo,
-    msg: InstantiateMsg,
-) -> StdResult<Response> {
-    CONFIGURATION.save(
-        deps.storage,
-        &Config {
-            governance: deps.api.addr_canonicalize(&msg.governance)?,
-            funder: deps.api.addr_canonicalize(&msg.funder)?,
-            staking_token: deps.api.addr_canonicalize(&msg.staking_token)?,
-            reward_asset_info: msg.reward_asset_info.to_raw(deps.api)?,
-        },
-    )?;
-
-    STATE.save(deps.storage, &State::default())?;
-
-    Ok(Response::default())
-}
-
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
-    let sender = info.sender.to_string();
-    match msg {
-        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
-        ExecuteMsg::Withdraw { amount } => withdraw(deps, sender, amount),
-        ExecuteMsg::Claim {} => claim(deps, sender),
-        ExecuteMsg::Fund {} => {
-            let amount = get_received_native_fund(deps.storage, info)?;
-            fund(deps, sender, amount)
-        }
-        ExecuteMsg::UpdateConfig { governance, funder } => {
-            update_config(deps, sender, governance, funder)
-        }
-    }
-}
-
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::Config {} => to_binary(&query_config(deps)?),
-        QueryMsg::State {} => to_binary(&query_state(deps)?),
-        QueryMsg::UserInfo { user } => to_binary(&query_user_info(deps, user)?),
-    }
-}
-
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::default())
-}
-
-pub fn receive_cw20(
-    deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
-    cw20_msg: Cw20ReceiveMsg,
-) -> StdResult<Response> {
-    let config = CONFIGURATION.load(deps.storage)?;
-    match from_binary(&cw20_msg.msg)? {
-        Cw20HookMsg::Deposit {} => {
-            if deps.api.addr_humanize(&config.staking_token)? == info.sender.clone() {
-                return deposit(deps, cw20_msg.sender, cw20_msg.amount);
-            }
-            Err(StdError::generic_err("invalid staking token"))
-        }
-        Cw20HookMsg::Fund {} => {
-            if let AssetInfoRaw::Token { contract_addr } = config.reward_asset_info {
-                if deps.api.addr_humanize(&contract_addr)? == info.sender.clone() {
-                    return fund(deps, cw20_msg.sender, cw20_msg.amount);
-                }
-            }
-            Err(StdError::generic_err("invalid reward token"))
-        }
-    }
-}
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Reply,
+    Response, StdResult, SubMsg, Uint128, WasmMsg,
+};
+
+use cw20::{Cw20ReceiveMsg, MinterResponse};
+use reward_pool::reward_pool::{
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PauseFlags, QueryMsg,
+    ReceiptTokenInstantiateMsg, RewardState, State,
+};
+use terraswap::asset::AssetInfoRaw;
+
+use crate::{
+    error::ContractError,
+    execute::{
+        accept_governance, add_hook, add_reward_asset, add_swap_pair, approve,
+        cancel_governance_proposal, claim, claim_for, claim_unbonded, complete_claim_swap,
+        compound, deposit, distribute_tax_reserve, emergency_withdraw, fund, fund_stream,
+        ignore_hook_failure, propose_governance, receipt_transfer_hook, register_receipt_token,
+        remove_hook, remove_swap_pair, revoke, set_auto_compound, set_paused,
+        sweep_forfeited_rewards, update_config, update_funder, withdraw, withdraw_for,
+        RECEIPT_TOKEN_REPLY_ID, STAKE_CHANGED_HOOK_REPLY_ID, SWAP_REPLY_ID,
+    },
+    migration::{
+        migrate_legacy_storage, migrate_legacy_users, seed_stake_snapshots,
+        DEFAULT_MIGRATION_LIMIT, LEGACY_MIGRATION,
+    },
+    query::{
+        query_all_users, query_approvals, query_claimable_rewards, query_config,
+        query_fund_history, query_funders, query_hooks, query_list_members, query_locks,
+        query_member, query_reward_schedules, query_solvency, query_state, query_swap_pairs,
+        query_total_stake_at_height, query_total_weight, query_unbonds, query_user_info,
+        query_user_stake_at_height,
+    },
+    state::{Config, FunderInfo, CONFIGURATION, FUNDERS, REWARD_SCHEDULES, STATE},
+    utils::{
+        assert_min_fund_amounts, get_received_native_fund, get_received_native_stake,
+        get_reward_token_index, is_receipt_token, to_min_fund_amounts, validate_recipient,
+    },
+};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let mut reward_asset_infos: Vec<AssetInfoRaw> = vec![];
+    for asset_info in msg.reward_asset_infos.iter() {
+        let asset_info = asset_info.to_raw(deps.api)?;
+        if reward_asset_infos
+            .iter()
+            .any(|reward_asset_info| reward_asset_info.equal(&asset_info))
+        {
+            return Err(ContractError::DuplicateRewardAsset {
+                asset_info: asset_info.to_normal(deps.api)?.to_string(),
+            });
+        }
+        reward_asset_infos.push(asset_info);
+    }
+
+    for (index, lock_tier) in msg.lock_tiers.iter().enumerate() {
+        if lock_tier.duration == 0
+            || lock_tier.boost < Decimal256::one()
+            || msg.lock_tiers[..index]
+                .iter()
+                .any(|other| other.duration == lock_tier.duration)
+        {
+            return Err(ContractError::InvalidLockTier {
+                duration: lock_tier.duration,
+            });
+        }
+    }
+
+    STATE.save(
+        deps.storage,
+        &State {
+            total_deposits: Uint128::zero(),
+            total_weight: Uint128::zero(),
+            total_unbonding: Uint128::zero(),
+            staker_count: 0,
+            rewards: vec![RewardState::default(); reward_asset_infos.len()],
+        },
+    )?;
+
+    let guardian = msg
+        .guardian
+        .map(|guardian| deps.api.addr_canonicalize(&guardian))
+        .transpose()?;
+    if msg.tokens_per_weight.is_zero() {
+        return Err(ContractError::InvalidTokensPerWeight {});
+    }
+
+    let min_fund_amounts =
+        to_min_fund_amounts(deps.api, &reward_asset_infos, &msg.min_fund_amounts)?;
+    let config = Config {
+        governance: deps.api.addr_canonicalize(&msg.governance)?,
+        pending_governance: None,
+        guardian,
+        staking_asset_info: msg.staking_asset_info.to_raw(deps.api)?,
+        reward_asset_infos,
+        lock_tiers: msg.lock_tiers,
+        unbonding_period: msg.unbonding_period,
+        forfeit_mode: msg.forfeit_mode,
+        permissionless_funding: msg.permissionless_funding,
+        min_fund_amounts,
+        cw4_enabled: msg.cw4_enabled,
+        tokens_per_weight: msg.tokens_per_weight,
+        paused: PauseFlags::default(),
+        receipt_token: None,
+    };
+    assert_min_fund_amounts(deps.api, &config)?;
+    CONFIGURATION.save(deps.storage, &config)?;
+
+    REWARD_SCHEDULES.save(deps.storage, &vec![])?;
+
+    for funder in msg.funders.iter() {
+        FUNDERS.save(
+            deps.storage,
+            deps.api.addr_canonicalize(funder)?.as_slice(),
+            &FunderInfo {
+                enabled: true,
+                rewards: vec![],
+            },
+        )?;
+    }
+
+    let mut response = Response::default();
+    if let Some(receipt_token) = msg.receipt_token {
+        response = response.add_submessage(SubMsg::reply_on_success(
+            WasmMsg::Instantiate {
+                admin: None,
+                code_id: receipt_token.code_id,
+                msg: to_binary(&ReceiptTokenInstantiateMsg {
+                    name: receipt_token.name.clone(),
+                    symbol: receipt_token.symbol,
+                    decimals: receipt_token.decimals,
+                    initial_balances: vec![],
+                    mint: Some(MinterResponse {
+                        minter: env.contract.address.to_string(),
+                        cap: None,
+                    }),
+                })?,
+                funds: vec![],
+                label: receipt_token.name,
+            },
+            RECEIPT_TOKEN_REPLY_ID,
+        ));
+    }
+
+    Ok(response)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    if !matches!(msg, ExecuteMsg::MigrateUsers { .. })
+        && LEGACY_MIGRATION.may_load(deps.storage)?.is_some()
+    {
+        return Err(ContractError::MigrationPending {});
+    }
+
+    let sender = info.sender.to_string();
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::Deposit {
+            recipient,
+            lock_duration,
+        } => {
+            let config = CONFIGURATION.load(deps.storage)?;
+            let amount = get_received_native_stake(&config, &info)?;
+            let recipient = validate_recipient(deps.api, recipient, &sender)?;
+            deposit(deps, env, sender, recipient, amount, lock_duration)
+        }
+        ExecuteMsg::Withdraw { amount, recipient } => {
+            let recipient = validate_recipient(deps.api, recipient, &sender)?;
+            withdraw(deps, env, sender, amount, recipient, false)
+        }
+        ExecuteMsg::Claim { recipient, swap } => {
+            let recipient = validate_recipient(deps.api, recipient, &sender)?;
+            claim(deps, env, sender, recipient, swap)
+        }
+        ExecuteMsg::ClaimUnbonded {} => claim_unbonded(deps, env, sender),
+        ExecuteMsg::Compound {} => compound(deps, env, sender),
+        ExecuteMsg::SetAutoCompound { enabled } => set_auto_compound(deps, sender, enabled),
+        ExecuteMsg::Approve {
+            operator,
+            can_withdraw,
+            expires,
+        } => approve(deps, env, sender, operator, can_withdraw, expires),
+        ExecuteMsg::Revoke { operator } => revoke(deps, sender, operator),
+        ExecuteMsg::ClaimFor { user } => claim_for(deps, env, sender, user),
+        ExecuteMsg::WithdrawFor { user, amount } => withdraw_for(deps, env, sender, user, amount),
+        ExecuteMsg::EmergencyWithdraw {} => emergency_withdraw(deps, env, sender, None),
+        ExecuteMsg::Fund {} => {
+            let (reward_index, amount) = get_received_native_fund(deps.storage, info)?;
+            fund(deps, env, sender, reward_index, amount)
+        }
+        ExecuteMsg::FundStream {
+            start_time,
+            end_time,
+        } => {
+            let (reward_index, amount) = get_received_native_fund(deps.storage, info)?;
+            fund_stream(
+                deps,
+                env,
+                sender,
+                reward_index,
+                amount,
+                start_time,
+                end_time,
+            )
+        }
+        ExecuteMsg::UpdateConfig {
+            guardian,
+            unbonding_period,
+            forfeit_mode,
+            permissionless_funding,
+            min_fund_amounts,
+            cw4_enabled,
+            tokens_per_weight,
+        } => update_config(
+            deps,
+            sender,
+            guardian,
+            unbonding_period,
+            forfeit_mode,
+            permissionless_funding,
+            min_fund_amounts,
+            cw4_enabled,
+            tokens_per_weight,
+        ),
+        ExecuteMsg::ProposeGovernance {
+            governance,
+            expires_in,
+        } => propose_governance(deps, env, sender, governance, expires_in),
+        ExecuteMsg::AcceptGovernance {} => accept_governance(deps, env, sender),
+        ExecuteMsg::CancelGovernanceProposal {} => cancel_governance_proposal(deps, sender),
+        ExecuteMsg::UpdateFunder {
+            funder,
+            enabled,
+            caps,
+        } => update_funder(deps, sender, funder, enabled, caps),
+        ExecuteMsg::AddRewardAsset { asset_info } => add_reward_asset(deps, sender, asset_info),
+        ExecuteMsg::AddSwapPair { pair, asset_infos } => {
+            add_swap_pair(deps, sender, pair, asset_infos)
+        }
+        ExecuteMsg::RemoveSwapPair { pair } => remove_swap_pair(deps, sender, pair),
+        ExecuteMsg::AddHook { addr } => add_hook(deps, sender, addr),
+        ExecuteMsg::RemoveHook { addr } => remove_hook(deps, sender, addr),
+        ExecuteMsg::ReceiptTransferHook(msg) => receipt_transfer_hook(deps, env, sender, msg),
+        ExecuteMsg::SweepForfeitedRewards { recipient } => {
+            sweep_forfeited_rewards(deps, sender, recipient)
+        }
+        ExecuteMsg::DistributeTaxReserve { asset_info, amount } => {
+            distribute_tax_reserve(deps, env, sender, asset_info, amount)
+        }
+        ExecuteMsg::SetPaused {
+            deposit,
+            fund,
+            claim,
+            withdraw,
+        } => set_paused(deps, sender, deposit, fund, claim, withdraw),
+        ExecuteMsg::MigrateUsers { limit } => {
+            let (user_count, completed) = migrate_legacy_users(
+                deps.storage,
+                env.block.height,
+                limit.unwrap_or(DEFAULT_MIGRATION_LIMIT),
+            )?;
+
+            Ok(Response::new().add_attributes(vec![
+                attr("action", "migrate_users"),
+                attr("migrated_users", user_count.to_string()),
+                attr("completed", completed.to_string()),
+            ]))
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::State {} => to_binary(&query_state(deps)?),
+        QueryMsg::UserInfo { user } => to_binary(&query_user_info(deps, env, user)?),
+        QueryMsg::AllUsers { start_after, limit } => {
+            to_binary(&query_all_users(deps, env, start_after, limit)?)
+        }
+        QueryMsg::UserStakeAtHeight { user, height } => {
+            to_binary(&query_user_stake_at_height(deps, user, height)?)
+        }
+        QueryMsg::TotalStakeAtHeight { height } => {
+            to_binary(&query_total_stake_at_height(deps, height)?)
+        }
+        QueryMsg::ClaimableRewards { user } => {
+            to_binary(&query_claimable_rewards(deps, env, user)?)
+        }
+        QueryMsg::RewardSchedules {} => to_binary(&query_reward_schedules(deps)?),
+        QueryMsg::Solvency {} => to_binary(&query_solvency(deps, env)?),
+        QueryMsg::Locks { user } => to_binary(&query_locks(deps, user)?),
+        QueryMsg::Unbonds { user } => to_binary(&query_unbonds(deps, user)?),
+        QueryMsg::Funders { start_after, limit } => {
+            to_binary(&query_funders(deps, start_after, limit)?)
+        }
+        QueryMsg::FundHistory { start_after, limit } => {
+            to_binary(&query_fund_history(deps, start_after, limit)?)
+        }
+        QueryMsg::Approvals {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_approvals(deps, owner, start_after, limit)?),
+        QueryMsg::SwapPairs { start_after, limit } => {
+            to_binary(&query_swap_pairs(deps, start_after, limit)?)
+        }
+        QueryMsg::Hooks {} => to_binary(&query_hooks(deps)?),
+        QueryMsg::Member { addr, at_height } => to_binary(&query_member(deps, addr, at_height)?),
+        QueryMsg::TotalWeight { at_height } => to_binary(&query_total_weight(deps, at_height)?),
+        QueryMsg::ListMembers { start_after, limit } => {
+            to_binary(&query_list_members(deps, start_after, limit)?)
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        SWAP_REPLY_ID => complete_claim_swap(deps, env),
+        RECEIPT_TOKEN_REPLY_ID => register_receipt_token(deps, msg),
+        STAKE_CHANGED_HOOK_REPLY_ID => ignore_hook_failure(msg),
+        id => Err(ContractError::UnknownReplyId { id }),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let mut response = Response::new().add_attribute("action", "migrate");
+    if migrate_legacy_storage(deps.storage, env.block.height)? {
+        let (user_count, completed) =
+            migrate_legacy_users(deps.storage, env.block.height, DEFAULT_MIGRATION_LIMIT)?;
+        response = response.add_attributes(vec![
+            attr("migrated_users", user_count.to_string()),
+            attr("completed", completed.to_string()),
+        ]);
+    } else {
+        let seeded_count = seed_stake_snapshots(deps.storage, env.block.height)?;
+        response = response.add_attribute("seeded_snapshots", seeded_count.to_string());
+    }
+
+    Ok(response)
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIGURATION.load(deps.storage)?;
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Deposit {
+            recipient,
+            lock_duration,
+        } => {
+            let staking_token = match &config.staking_asset_info {
+                AssetInfoRaw::Token { contract_addr } => {
+                    Some(deps.api.addr_humanize(contract_addr)?)
+                }
+                AssetInfoRaw::NativeToken { .. } => None,
+            };
+            if staking_token == Some(info.sender.clone()) {
+                let recipient = validate_recipient(deps.api, recipient, &cw20_msg.sender)?;
+                return deposit(
+                    deps,
+                    env,
+                    cw20_msg.sender,
+                    recipient,
+                    cw20_msg.amount,
+                    lock_duration,
+                );
+            }
+            Err(ContractError::InvalidStakingToken {
+                token: info.sender.to_string(),
+            })
+        }
+        Cw20HookMsg::Withdraw { recipient } => {
+            if !is_receipt_token(deps.api, &config, info.sender.as_str())? {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let recipient = validate_recipient(deps.api, recipient, &cw20_msg.sender)?;
+            withdraw(deps, env, cw20_msg.sender, cw20_msg.amount, recipient, true)
+        }
+        Cw20HookMsg::EmergencyWithdraw {} => {
+            if !is_receipt_token(deps.api, &config, info.sender.as_str())? {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            emergency_withdraw(deps, env, cw20_msg.sender, Some(cw20_msg.amount))
+        }
+        Cw20HookMsg::Fund {} => {
+            let reward_index = get_reward_token_index(deps.api, &config, info.sender.as_str())?;
+            fund(deps, env, cw20_msg.sender, reward_index, cw20_msg.amount)
+        }
+        Cw20HookMsg::FundStream {
+            start_time,
+            end_time,
+        } => {
+            let reward_index = get_reward_token_index(deps.api, &config, info.sender.as_str())?;
+            fund_stream(
+                deps,
+                env,
+                cw20_msg.sender,
+                reward_index,
+                cw20_msg.amount,
+                start_time,
+                end_time,
+            )
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("unauthorized")]
+    Unauthorized {},
+
+    #[error("amount is zero")]
+    ZeroAmount {},
+
+    #[error("no stake")]
+    NoStake {},
+
+    #[error("reward is zero")]
+    ZeroReward {},
+
+    #[error("insufficient stake: requested {requested}, available {available}")]
+    InsufficientStake {
+        requested: Uint128,
+        available: Uint128,
+    },
+
+    #[error("insufficient tax reserve: requested {requested}, available {available}")]
+    InsufficientTaxReserve {
+        requested: Uint128,
+        available: Uint128,
+    },
+
+    #[error("invalid denom received: expected 1 coin, got {received}")]
+    InvalidDenomReceived { received: usize },
+
+    #[error("not support denom reward: {denom}")]
+    UnsupportedDenomReward { denom: String },
+
+    #[error("invalid reward token: {token}")]
+    InvalidRewardToken { token: String },
+
+    #[error("invalid staking token: {token}")]
+    InvalidStakingToken { token: String },
+
+    #[error("invalid schedule: start time {start_time}, end time {end_time}")]
+    InvalidSchedule { start_time: u64, end_time: u64 },
+
+    #[error("duplicate reward asset: {asset_info}")]
+    DuplicateRewardAsset { asset_info: String },
+
+    #[error("invalid lock tier with duration {duration}")]
+    InvalidLockTier { duration: u64 },
+
+    #[error("invalid lock duration {duration}")]
+    InvalidLockDuration { duration: u64 },
+
+    #[error("cannot lock the deposit of another user")]
+    CannotLockForOther {},
+
+    #[error("too many lock positions, at most {max} can be open")]
+    TooManyLocks { max: usize },
+
+    #[error("tokens per weight must not be zero")]
+    InvalidTokensPerWeight {},
+
+    #[error("no released unbonds")]
+    NoReleasedUnbonds {},
+
+    #[error("{action} is paused")]
+    Paused { action: String },
+
+    #[error("no pending handover")]
+    NoPendingHandover {},
+
+    #[error("handover expired at {expires_at}")]
+    HandoverExpired { expires_at: u64 },
+
+    #[error("unsupported reward asset {asset_info}")]
+    UnsupportedRewardAsset { asset_info: String },
+
+    #[error("funding cap exceeded: requested {requested}, remaining {remaining}")]
+    FundingCapExceeded {
+        requested: Uint128,
+        remaining: Uint128,
+    },
+
+    #[error("invalid funding cap of {asset_info}")]
+    InvalidFundingCap { asset_info: String },
+
+    #[error("fund amount {amount} is smaller than the minimum {min_amount}")]
+    FundAmountTooSmall {
+        amount: Uint128,
+        min_amount: Uint128,
+    },
+
+    #[error("permissionless funding needs a minimum fund amount of {asset_info}")]
+    MinFundAmountRequired { asset_info: String },
+
+    #[error("approval expired")]
+    ApprovalExpired {},
+
+    #[error("cannot approve own account")]
+    CannotApproveSelf {},
+
+    #[error("staking token is not a reward asset")]
+    StakingTokenNotRewarded {},
+
+    #[error("no swap pair from {offer} to {ask}")]
+    NoSwapPair { offer: String, ask: String },
+
+    #[error("max slippage must not exceed 1")]
+    InvalidSlippage {},
+
+    #[error("swap returned {received}, less than the minimum {minimum_receive}")]
+    SwapSlippageExceeded {
+        received: Uint128,
+        minimum_receive: Uint128,
+    },
+
+    #[error("stake is withdrawn by sending the receipts to the pool")]
+    ReceiptsRequired {},
+
+    #[error(
+        "received {received} receipts, but the emergency withdrawal needs the whole stake {stake}"
+    )]
+    ReceiptAmountMismatch { received: Uint128, stake: Uint128 },
+
+    #[error("receipt token address not found")]
+    ReceiptTokenNotFound {},
+
+    #[error("the conversion of the users is not completed, see MigrateUsers")]
+    MigrationPending {},
+
+    #[error("no pending migration")]
+    NoPendingMigration {},
+
+    #[error("unknown reply id {id}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("no pending swap")]
+    NoPendingSwap {},
+
+    #[error("hook {addr} is already registered")]
+    HookAlreadyRegistered { addr: String },
+
+    #[error("hook {addr} is not registered")]
+    HookNotRegistered { addr: String },
+}
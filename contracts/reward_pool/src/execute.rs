@@ -1,147 +1,1866 @@
-use cosmwasm_std::{attr, Addr, Decimal, DepsMut, Response, StdError, StdResult, Uint128};
-
-use terraswap::asset::{Asset, AssetInfo};
-
-use crate::state::{CONFIGURATION, STATE, USER_INFO};
-
-pub fn deposit(deps: DepsMut, user: String, amount: Uint128) -> StdResult<Response> {
-    if amount.is_zero() {
-        return Err(StdError::generic_err("amount is zero"));
-    }
-
-    let mut state = STATE.load(deps.storage)?;
-    let mut user_info = USER_INFO
-        .load(deps.storage, deps.api.addr_canonicalize(&user)?.as_slice())
-        .unwrap_or_default();
-    let new_reward = user_info.stake_amount * state.acc_per_share - user_info.reward_debt;
-    user_info.pending_amount += new_reward;
-    user_info.stake_amount += amount;
-    user_info.reward_debt = user_info.stake_amount * state.acc_per_share;
-
-    USER_INFO.save(
-        deps.storage,
-        deps.api.addr_canonicalize(&user)?.as_slice(),
-        &user_info,
-    )?;
-
-    state.total_deposits += amount;
-    STATE.save(deps.storage, &state)?;
-
-    Ok(Response::new().add_attributes(vec![attr("action", "deposit"), attr("amount", amount)]))
-}
-
-pub fn withdraw(deps: DepsMut, user: String, amount: Uint128) -> StdResult<Response> {
-    if amount.is_zero() {
-        return Err(StdError::generic_err("amount is zero"));
-    }
-
-    let config = CONFIGURATION.load(deps.storage)?;
-    let mut state = STATE.load(deps.storage)?;
-    let mut user_info = USER_INFO
-        .load(deps.storage, deps.api.addr_canonicalize(&user)?.as_slice())
-        .unwrap_or_default();
-    let new_reward = user_info.stake_amount * state.acc_per_share - user_info.reward_debt;
-    user_info.pending_amount += new_reward;
-    user_info.stake_amount -= amount;
-    user_info.reward_debt = user_info.stake_amount * state.acc_per_share;
-
-    USER_INFO.save(
-        deps.storage,
-        deps.api.addr_canonicalize(&user)?.as_slice(),
-        &user_info,
-    )?;
-
-    state.total_deposits -= amount;
-    STATE.save(deps.storage, &state)?;
-
-    let asset: Asset = Asset {
-        info: AssetInfo::Token {
-            contract_addr: deps.api.addr_humanize(&config.staking_token)?.to_string(),
-        },
-        amount,
-    };
-
-    Ok(Response::new()
-        .add_attributes(vec![attr("action", "withdraw"), attr("amount", amount)])
-        .add_message(asset.into_msg(&deps.querier, Addr::unchecked(user))?))
-}
-
-pub fn claim(deps: DepsMut, user: String) -> StdResult<Response> {
-    let config = CONFIGURATION.load(deps.storage)?;
-    let state = STATE.load(deps.storage)?;
-    let mut user_info = USER_INFO
-        .load(deps.storage, deps.api.addr_canonicalize(&user)?.as_slice())
-        .unwrap_or_default();
-    let new_reward = user_info.stake_amount * state.acc_per_share - user_info.reward_debt;
-    let pending_reward = user_info.pending_amount + new_reward;
-    user_info.pending_amount = Uint128::zero();
-    user_info.reward_debt = user_info.stake_amount * state.acc_per_share;
-
-    USER_INFO.save(
-        deps.storage,
-        deps.api.addr_canonicalize(&user)?.as_slice(),
-        &user_info,
-    )?;
-
-    if pending_reward.is_zero() {
-        return Err(StdError::generic_err("reward is zero"));
-    }
-
-    let asset: Asset = Asset {
-        info: config.reward_asset_info.to_normal(deps.api)?,
-        amount: pending_reward,
-    };
-
-    Ok(Response::new()
-        .add_attributes(vec![
-            attr("action", "claim"),
-            attr("amount", pending_reward),
-        ])
-        .add_message(asset.into_msg(&deps.querier, Addr::unchecked(user))?))
-}
-
-pub fn fund(deps: DepsMut, funder: String, amount: Uint128) -> StdResult<Response> {
-    let config = CONFIGURATION.load(deps.storage)?;
-
-    if deps.api.addr_humanize(&config.funder)?.to_string() != funder {
-        return Err(StdError::generic_err("unauthorized"));
-    }
-
-    if amount.is_zero() {
-        return Err(StdError::generic_err("amount is zero"));
-    }
-
-    let mut state = STATE.load(deps.storage)?;
-    if state.total_deposits.is_zero() {
-        return Err(StdError::generic_err("no deposits"));
-    }
-    state.acc_per_share = state.acc_per_share + Decimal::from_ratio(amount, state.total_deposits);
-    STATE.save(deps.storage, &state)?;
-
-    Ok(Response::new().add_attributes(vec![attr("action", "fund"), attr("amount", amount)]))
-}
-
-pub fn update_config(
-    deps: DepsMut,
-    sender: String,
-    governance: Option<String>,
-    funder: Option<String>,
-) -> StdResult<Response> {
-    let mut config = CONFIGURATION.load(deps.storage)?;
-
-    if deps.api.addr_humanize(&config.governance)?.to_string() != sender {
-        return Err(StdError::generic_err("unauthorized"));
-    }
-
-    if let Some(governance) = governance {
-        config.governance = deps.api.addr_canonicalize(&governance)?;
-    }
-
-    if let Some(funder) = funder {
-        config.funder = deps.api.addr_canonicalize(&funder)?;
-    }
-
-    CONFIGURATION.save(deps.storage, &config)?;
-
-    Ok(Response::new().add_attribute("action", "update_config"))
-}
+use cosmwasm_std::{
+    attr, to_binary, Addr, CanonicalAddr, Coin, ContractResult, CosmosMsg, Decimal, Deps, DepsMut,
+    Env, Order, OverflowError, OverflowOperation, Reply, Response, StdError, StdResult, Storage,
+    SubMsg, Uint128, WasmMsg,
+};
+
+use cw20::{Cw20ExecuteMsg, Expiration};
+use cw_storage_plus::U64Key;
+use reward_pool::reward_pool::{
+    ClaimSwap, ForfeitMode, FundingCap, LockPosition, ReceiptTransferHookMsg, RewardSchedule,
+    RewardState, StakeChangedHookMsg, State, Unbond,
+};
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::pair::{Cw20HookMsg as PairCw20HookMsg, ExecuteMsg as PairExecuteMsg};
+
+use crate::{
+    error::ContractError,
+    state::{
+        Config, FundRecordRaw, FunderReward, OperatorApproval, PendingHandoverRaw, PendingSwap,
+        UserInfo, APPROVALS, CONFIGURATION, FUNDERS, FUND_HISTORY, FUND_HISTORY_COUNT, HOOKS,
+        LOCK_EXPIRIES, PENDING_SWAP, REWARD_SCHEDULES, STATE, SWAP_PAIRS, UNBONDS, USER_INFO,
+    },
+    utils::{
+        add_lock, add_seconds, assert_min_fund_amounts, compound_rewards, compute_reserved_tax,
+        compute_unlocked_amount, distribute_reward, get_staking_reward_index, into_reward_msg,
+        is_receipt_token, pay_reward_tax, record_funding, release_expired_locks, release_rewards,
+        reset_reward_debts, save_lock_expiry, save_user_info, settle_rewards, snapshot_stakes,
+        to_min_fund_amounts, update_staker_count, update_weight,
+    },
+};
+
+/// Enabled funders can fund within their caps. Anyone else can fund at least the minimum
+/// fund amount of the asset if the funding is permissionless and `allow_permissionless` is set.
+/// Assets without a minimum, e.g. added after the funding was opened, can not be funded permissionlessly.
+fn authorize_funding(
+    deps: DepsMut,
+    config: &Config,
+    funder_raw: &CanonicalAddr,
+    reward_index: usize,
+    amount: Uint128,
+    time: u64,
+    allow_permissionless: bool,
+) -> Result<(), ContractError> {
+    match FUNDERS.may_load(deps.storage, funder_raw.as_slice())? {
+        Some(mut funder_info) if funder_info.enabled => {
+            record_funding(&mut funder_info, reward_index, amount, time)?;
+            FUNDERS.save(deps.storage, funder_raw.as_slice(), &funder_info)?;
+            Ok(())
+        }
+        _ if config.permissionless_funding && allow_permissionless => {
+            let min_amount = config
+                .min_fund_amounts
+                .get(reward_index)
+                .cloned()
+                .unwrap_or_default();
+            if min_amount.is_zero() {
+                return Err(ContractError::MinFundAmountRequired {
+                    asset_info: config.reward_asset_infos[reward_index]
+                        .to_normal(deps.api)?
+                        .to_string(),
+                });
+            }
+            if amount < min_amount {
+                return Err(ContractError::FundAmountTooSmall { amount, min_amount });
+            }
+            Ok(())
+        }
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+/// Appends the record to the funding history with the next id
+fn save_fund_record(storage: &mut dyn Storage, mut record: FundRecordRaw) -> StdResult<()> {
+    record.id = FUND_HISTORY_COUNT.may_load(storage)?.unwrap_or_default();
+    let count = record.id.checked_add(1).ok_or_else(|| {
+        StdError::overflow(OverflowError::new(OverflowOperation::Add, record.id, 1))
+    })?;
+    FUND_HISTORY_COUNT.save(storage, &count)?;
+    FUND_HISTORY.save(storage, U64Key::from(record.id), &record)
+}
+
+/// Checks that `sender` is the nominee of a handover which has not expired
+fn assert_handover_acceptable(
+    pending: &Option<PendingHandoverRaw>,
+    sender: &CanonicalAddr,
+    time: u64,
+) -> Result<(), ContractError> {
+    let pending = pending
+        .as_ref()
+        .ok_or(ContractError::NoPendingHandover {})?;
+
+    if pending.address != *sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if pending.expires_at <= time {
+        return Err(ContractError::HandoverExpired {
+            expires_at: pending.expires_at,
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that `operator` holds an unexpired approval of `owner`
+/// which allows withdrawals if `withdraw` is set
+fn assert_approved(
+    deps: Deps,
+    env: &Env,
+    owner: &str,
+    operator: &str,
+    withdraw: bool,
+) -> Result<(), ContractError> {
+    let approval = APPROVALS
+        .may_load(
+            deps.storage,
+            (
+                deps.api.addr_canonicalize(owner)?.as_slice(),
+                deps.api.addr_canonicalize(operator)?.as_slice(),
+            ),
+        )?
+        .ok_or(ContractError::Unauthorized {})?;
+
+    if approval.expires.is_expired(&env.block) {
+        return Err(ContractError::ApprovalExpired {});
+    }
+
+    if withdraw && !approval.can_withdraw {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Ok(())
+}
+
+fn assert_not_paused(paused: bool, action: &str) -> Result<(), ContractError> {
+    if paused {
+        return Err(ContractError::Paused {
+            action: action.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Messages notifying the hook contracts of a stake change of `user`
+fn stake_changed_hook_messages(
+    deps: Deps,
+    user: &str,
+    old_stake: Uint128,
+    new_stake: Uint128,
+) -> StdResult<Vec<SubMsg>> {
+    if old_stake == new_stake {
+        return Ok(vec![]);
+    }
+
+    HOOKS
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .iter()
+        .map(|hook| {
+            let msg = StakeChangedHookMsg {
+                user: user.to_string(),
+                old_stake,
+                new_stake,
+            };
+            Ok(SubMsg::new(
+                msg.into_cosmos_msg(deps.api.addr_humanize(hook)?)?,
+            ))
+        })
+        .collect()
+}
+
+/// Compounds the pending staking token rewards if the user enabled auto compounding
+fn auto_compound(
+    config: &Config,
+    state: &mut State,
+    user_info: &mut UserInfo,
+) -> StdResult<Uint128> {
+    match get_staking_reward_index(config) {
+        Some(reward_index) if user_info.auto_compound => {
+            compound_rewards(state, user_info, reward_index)
+        }
+        _ => Ok(Uint128::zero()),
+    }
+}
+
+/// Mints `mint_amount` receipts to `user` and burns `burn_amount` of the receipts which
+/// were sent to the pool for a withdrawal, if the pool has a receipt token
+fn receipt_token_messages(
+    deps: Deps,
+    config: &Config,
+    user: &str,
+    mint_amount: Uint128,
+    burn_amount: Uint128,
+) -> StdResult<Vec<CosmosMsg>> {
+    let receipt_token = match &config.receipt_token {
+        Some(receipt_token) => deps.api.addr_humanize(receipt_token)?,
+        None => return Ok(vec![]),
+    };
+
+    let mut msgs: Vec<Cw20ExecuteMsg> = vec![];
+    if !mint_amount.is_zero() {
+        msgs.push(Cw20ExecuteMsg::Mint {
+            recipient: user.to_string(),
+            amount: mint_amount,
+        });
+    }
+    if !burn_amount.is_zero() {
+        msgs.push(Cw20ExecuteMsg::Burn {
+            amount: burn_amount,
+        });
+    }
+
+    msgs.into_iter()
+        .map(|msg| {
+            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: receipt_token.to_string(),
+                msg: to_binary(&msg)?,
+                funds: vec![],
+            }))
+        })
+        .collect()
+}
+
+pub const SWAP_REPLY_ID: u64 = 1;
+pub const RECEIPT_TOKEN_REPLY_ID: u64 = 2;
+pub const STAKE_CHANGED_HOOK_REPLY_ID: u64 = 3;
+
+/// Releases the streamed rewards up to the current block time
+/// and drops the boost of the expired lock positions.
+/// Must be called before any user or state accounting is touched.
+fn update_rewards(storage: &mut dyn Storage, env: &Env, state: &mut State) -> StdResult<()> {
+    let mut schedules = REWARD_SCHEDULES.may_load(storage)?.unwrap_or_default();
+    let released = release_rewards(storage, state, &mut schedules, env.block.time.seconds())?;
+    for (user_raw, user_info) in released.users {
+        save_user_info(storage, &user_raw, &user_info)?;
+    }
+    for expiry in released.expiries {
+        if expiry.users.is_empty() {
+            LOCK_EXPIRIES.remove(storage, U64Key::from(expiry.end_time));
+        } else {
+            LOCK_EXPIRIES.save(storage, U64Key::from(expiry.end_time), &expiry)?;
+        }
+    }
+    REWARD_SCHEDULES.save(storage, &schedules)
+}
+
+/// Stakes `amount` sent by `depositor` for `user`
+pub fn deposit(
+    deps: DepsMut,
+    env: Env,
+    depositor: String,
+    user: String,
+    amount: Uint128,
+    lock_duration: Option<u64>,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    // a lock on behalf of another user would keep that user from withdrawing
+    if lock_duration.is_some() && depositor != user {
+        return Err(ContractError::CannotLockForOther {});
+    }
+
+    let config = CONFIGURATION.load(deps.storage)?;
+    assert_not_paused(config.paused.deposit, "deposit")?;
+    let lock_tier = match lock_duration {
+        Some(duration) => Some(
+            config
+                .lock_tiers
+                .iter()
+                .find(|lock_tier| lock_tier.duration == duration)
+                .cloned()
+                .ok_or(ContractError::InvalidLockDuration { duration })?,
+        ),
+        None => None,
+    };
+
+    let mut state = STATE.load(deps.storage)?;
+    update_rewards(deps.storage, &env, &mut state)?;
+    let mut user_info = USER_INFO
+        .load(deps.storage, deps.api.addr_canonicalize(&user)?.as_slice())
+        .unwrap_or_default();
+    let prev_stake_amount = user_info.stake_amount;
+    settle_rewards(&state, &mut user_info)?;
+    auto_compound(&config, &mut state, &mut user_info)?;
+    release_expired_locks(&mut user_info, env.block.time.seconds());
+    user_info.stake_amount = user_info.stake_amount.checked_add(amount)?;
+    if let Some(lock_tier) = lock_tier {
+        let end_time = add_seconds(env.block.time.seconds(), lock_tier.duration)?;
+        add_lock(
+            &mut user_info,
+            LockPosition {
+                amount,
+                boost: lock_tier.boost,
+                start_time: env.block.time.seconds(),
+                end_time,
+            },
+        )?;
+        save_lock_expiry(deps.storage, &deps.api.addr_canonicalize(&user)?, end_time)?;
+    }
+    update_weight(&mut state, &mut user_info)?;
+    reset_reward_debts(&state, &mut user_info)?;
+
+    update_staker_count(&mut state, prev_stake_amount, user_info.stake_amount)?;
+    save_user_info(
+        deps.storage,
+        &deps.api.addr_canonicalize(&user)?,
+        &user_info,
+    )?;
+
+    state.total_deposits = state.total_deposits.checked_add(amount)?;
+    for reward_index in 0..state.rewards.len() {
+        distribute_reward(&mut state, reward_index, Uint128::zero())?;
+    }
+    snapshot_stakes(
+        deps.storage,
+        &deps.api.addr_canonicalize(&user)?,
+        user_info.stake_amount,
+        state.total_deposits,
+        env.block.height,
+    )?;
+    STATE.save(deps.storage, &state)?;
+
+    let receipt_messages = receipt_token_messages(
+        deps.as_ref(),
+        &config,
+        &user,
+        user_info.stake_amount.checked_sub(prev_stake_amount)?,
+        Uint128::zero(),
+    )?;
+    let hook_messages = stake_changed_hook_messages(
+        deps.as_ref(),
+        &user,
+        prev_stake_amount,
+        user_info.stake_amount,
+    )?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "deposit"),
+            attr("depositor", depositor),
+            attr("beneficiary", user),
+            attr("amount", amount),
+        ])
+        .add_messages(receipt_messages)
+        .add_submessages(hook_messages))
+}
+
+/// Withdraws `amount` of the stake of `user` to `recipient`
+pub fn withdraw(
+    deps: DepsMut,
+    env: Env,
+    user: String,
+    amount: Uint128,
+    recipient: String,
+    receipts_returned: bool,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let config = CONFIGURATION.load(deps.storage)?;
+    assert_not_paused(config.paused.withdraw, "withdraw")?;
+    if config.receipt_token.is_some() && !receipts_returned {
+        return Err(ContractError::ReceiptsRequired {});
+    }
+    let mut state = STATE.load(deps.storage)?;
+    update_rewards(deps.storage, &env, &mut state)?;
+    let mut user_info = USER_INFO
+        .load(deps.storage, deps.api.addr_canonicalize(&user)?.as_slice())
+        .unwrap_or_default();
+    let prev_stake_amount = user_info.stake_amount;
+    settle_rewards(&state, &mut user_info)?;
+    let compounded_amount = auto_compound(&config, &mut state, &mut user_info)?;
+    release_expired_locks(&mut user_info, env.block.time.seconds());
+    let unlocked_amount = compute_unlocked_amount(&user_info)?;
+    if amount > unlocked_amount {
+        return Err(ContractError::InsufficientStake {
+            requested: amount,
+            available: unlocked_amount,
+        });
+    }
+    user_info.stake_amount = user_info.stake_amount.checked_sub(amount)?;
+    update_weight(&mut state, &mut user_info)?;
+    reset_reward_debts(&state, &mut user_info)?;
+
+    update_staker_count(&mut state, prev_stake_amount, user_info.stake_amount)?;
+    save_user_info(
+        deps.storage,
+        &deps.api.addr_canonicalize(&user)?,
+        &user_info,
+    )?;
+
+    state.total_deposits = state.total_deposits.checked_sub(amount)?;
+    snapshot_stakes(
+        deps.storage,
+        &deps.api.addr_canonicalize(&user)?,
+        user_info.stake_amount,
+        state.total_deposits,
+        env.block.height,
+    )?;
+    let receipt_messages =
+        receipt_token_messages(deps.as_ref(), &config, &user, compounded_amount, amount)?;
+    let hook_messages = stake_changed_hook_messages(
+        deps.as_ref(),
+        &user,
+        prev_stake_amount,
+        user_info.stake_amount,
+    )?;
+
+    if config.unbonding_period == 0 {
+        STATE.save(deps.storage, &state)?;
+
+        let asset: Asset = Asset {
+            info: config.staking_asset_info.to_normal(deps.api)?,
+            amount,
+        };
+
+        return Ok(Response::new()
+            .add_attributes(vec![
+                attr("action", "withdraw"),
+                attr("recipient", recipient.clone()),
+                attr("amount", amount),
+            ])
+            .add_message(asset.into_msg(&deps.querier, Addr::unchecked(recipient))?)
+            .add_messages(receipt_messages)
+            .add_submessages(hook_messages));
+    }
+
+    state.total_unbonding = state.total_unbonding.checked_add(amount)?;
+    STATE.save(deps.storage, &state)?;
+
+    let release_time = add_seconds(env.block.time.seconds(), config.unbonding_period)?;
+    let user_raw = deps.api.addr_canonicalize(&user)?;
+    let mut unbonds = UNBONDS
+        .may_load(deps.storage, user_raw.as_slice())?
+        .unwrap_or_default();
+    unbonds.push(Unbond {
+        amount,
+        release_time,
+        recipient: recipient.clone(),
+    });
+    UNBONDS.save(deps.storage, user_raw.as_slice(), &unbonds)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "withdraw"),
+            attr("recipient", recipient),
+            attr("amount", amount),
+            attr("release_time", release_time.to_string()),
+        ])
+        .add_messages(receipt_messages)
+        .add_submessages(hook_messages))
+}
+
+pub fn claim_unbonded(deps: DepsMut, env: Env, user: String) -> Result<Response, ContractError> {
+    let config = CONFIGURATION.load(deps.storage)?;
+    assert_not_paused(config.paused.withdraw, "withdraw")?;
+    let user_raw = deps.api.addr_canonicalize(&user)?;
+    let unbonds = UNBONDS
+        .may_load(deps.storage, user_raw.as_slice())?
+        .unwrap_or_default();
+
+    let (released, unbonds): (Vec<Unbond>, Vec<Unbond>) = unbonds
+        .into_iter()
+        .partition(|unbond| unbond.release_time <= env.block.time.seconds());
+    if released.is_empty() {
+        return Err(ContractError::NoReleasedUnbonds {});
+    }
+
+    // released entries are merged per recipient
+    let mut amount = Uint128::zero();
+    let mut transfers: Vec<(String, Uint128)> = vec![];
+    for unbond in released.into_iter() {
+        amount = amount.checked_add(unbond.amount)?;
+        match transfers
+            .iter_mut()
+            .find(|(recipient, _)| *recipient == unbond.recipient)
+        {
+            Some(transfer) => transfer.1 = transfer.1.checked_add(unbond.amount)?,
+            None => transfers.push((unbond.recipient, unbond.amount)),
+        }
+    }
+
+    if unbonds.is_empty() {
+        UNBONDS.remove(deps.storage, user_raw.as_slice());
+    } else {
+        UNBONDS.save(deps.storage, user_raw.as_slice(), &unbonds)?;
+    }
+
+    let mut state = STATE.load(deps.storage)?;
+    state.total_unbonding = state.total_unbonding.checked_sub(amount)?;
+    STATE.save(deps.storage, &state)?;
+
+    let staking_asset_info = config.staking_asset_info.to_normal(deps.api)?;
+    let messages = transfers
+        .into_iter()
+        .map(|(recipient, amount)| {
+            Asset {
+                info: staking_asset_info.clone(),
+                amount,
+            }
+            .into_msg(&deps.querier, Addr::unchecked(recipient))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "claim_unbonded"),
+            attr("amount", amount),
+        ])
+        .add_messages(messages))
+}
+
+/// Claims the pending rewards of `user` to `recipient`
+pub fn claim(
+    mut deps: DepsMut,
+    env: Env,
+    user: String,
+    recipient: String,
+    swap: Option<ClaimSwap>,
+) -> Result<Response, ContractError> {
+    let config = CONFIGURATION.load(deps.storage)?;
+    assert_not_paused(config.paused.claim, "claim")?;
+    let mut state = STATE.load(deps.storage)?;
+    update_rewards(deps.storage, &env, &mut state)?;
+    let mut user_info = USER_INFO
+        .load(deps.storage, deps.api.addr_canonicalize(&user)?.as_slice())
+        .unwrap_or_default();
+    let prev_stake_amount = user_info.stake_amount;
+    settle_rewards(&state, &mut user_info)?;
+    let compounded_amount = auto_compound(&config, &mut state, &mut user_info)?;
+
+    let mut pending_rewards: Vec<(usize, Asset)> = vec![];
+    for (reward_index, ((asset_info, reward), user_reward)) in config
+        .reward_asset_infos
+        .iter()
+        .zip(state.rewards.iter_mut())
+        .zip(user_info.rewards.iter_mut())
+        .enumerate()
+    {
+        if !user_reward.pending_amount.is_zero() {
+            reward.claimed_amount = reward
+                .claimed_amount
+                .checked_add(user_reward.pending_amount)?;
+            pending_rewards.push((
+                reward_index,
+                Asset {
+                    info: asset_info.to_normal(deps.api)?,
+                    amount: user_reward.pending_amount,
+                },
+            ));
+            user_reward.pending_amount = Uint128::zero();
+        }
+    }
+    release_expired_locks(&mut user_info, env.block.time.seconds());
+    update_weight(&mut state, &mut user_info)?;
+    reset_reward_debts(&state, &mut user_info)?;
+
+    if pending_rewards.is_empty() && compounded_amount.is_zero() {
+        return Err(ContractError::ZeroReward {});
+    }
+    let amount = pending_rewards
+        .iter()
+        .map(|(_, asset)| asset.to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+    let messages = match swap {
+        Some(swap) => claim_swap_messages(
+            deps.branch(),
+            &env,
+            &mut state,
+            pending_rewards,
+            &recipient,
+            swap,
+        )?,
+        None => pending_rewards
+            .into_iter()
+            .map(|(reward_index, asset)| {
+                Ok(SubMsg::new(into_reward_msg(
+                    &deps.querier,
+                    &mut state.rewards[reward_index],
+                    asset,
+                    Addr::unchecked(recipient.clone()),
+                )?))
+            })
+            .collect::<StdResult<Vec<_>>>()?,
+    };
+
+    update_staker_count(&mut state, prev_stake_amount, user_info.stake_amount)?;
+    save_user_info(
+        deps.storage,
+        &deps.api.addr_canonicalize(&user)?,
+        &user_info,
+    )?;
+    // the stake only changes if rewards were auto compounded
+    if user_info.stake_amount != prev_stake_amount {
+        snapshot_stakes(
+            deps.storage,
+            &deps.api.addr_canonicalize(&user)?,
+            user_info.stake_amount,
+            state.total_deposits,
+            env.block.height,
+        )?;
+    }
+    STATE.save(deps.storage, &state)?;
+
+    let receipt_messages = receipt_token_messages(
+        deps.as_ref(),
+        &config,
+        &user,
+        user_info.stake_amount.checked_sub(prev_stake_amount)?,
+        Uint128::zero(),
+    )?;
+    let hook_messages = stake_changed_hook_messages(
+        deps.as_ref(),
+        &user,
+        prev_stake_amount,
+        user_info.stake_amount,
+    )?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "claim"),
+            attr("recipient", recipient.clone()),
+            attr("amount", amount),
+        ])
+        .add_submessages(messages)
+        .add_messages(receipt_messages)
+        .add_submessages(hook_messages))
+}
+
+/// Swaps the claimed rewards to the ask asset through the whitelisted pairs.
+/// The pool receives the swapped amounts and forwards them to `recipient`
+/// together with the claimed ask asset rewards in the reply of the last swap.
+fn claim_swap_messages(
+    deps: DepsMut,
+    env: &Env,
+    state: &mut State,
+    rewards: Vec<(usize, Asset)>,
+    recipient: &str,
+    swap: ClaimSwap,
+) -> Result<Vec<SubMsg>, ContractError> {
+    if swap.max_slippage > Decimal::one() {
+        return Err(ContractError::InvalidSlippage {});
+    }
+
+    let mut ask_reward: Option<(usize, Asset)> = None;
+    let mut swap_messages: Vec<SubMsg> = vec![];
+    for (reward_index, asset) in rewards.into_iter() {
+        if asset.info == swap.ask_asset_info {
+            ask_reward = Some((reward_index, asset));
+            continue;
+        }
+
+        let (asset, _) = pay_reward_tax(&deps.querier, &mut state.rewards[reward_index], asset)?;
+        let pair = find_swap_pair(deps.as_ref(), &asset.info, &swap.ask_asset_info)?;
+        swap_messages.push(SubMsg::reply_on_success(
+            swap_msg(pair, asset, swap.max_slippage)?,
+            SWAP_REPLY_ID,
+        ));
+    }
+
+    let ask_amount = ask_reward
+        .as_ref()
+        .map_or_else(Uint128::zero, |(_, asset)| asset.amount);
+    if swap_messages.is_empty() {
+        if ask_amount < swap.minimum_receive {
+            return Err(ContractError::SwapSlippageExceeded {
+                received: ask_amount,
+                minimum_receive: swap.minimum_receive,
+            });
+        }
+
+        return ask_reward
+            .map(|(reward_index, asset)| {
+                Ok(SubMsg::new(into_reward_msg(
+                    &deps.querier,
+                    &mut state.rewards[reward_index],
+                    asset,
+                    Addr::unchecked(recipient),
+                )?))
+            })
+            .into_iter()
+            .collect();
+    }
+
+    let balance_before = swap
+        .ask_asset_info
+        .query_pool(&deps.querier, deps.api, env.contract.address.clone())?
+        .checked_sub(ask_amount)?;
+    PENDING_SWAP.save(
+        deps.storage,
+        &PendingSwap {
+            recipient: deps.api.addr_canonicalize(recipient)?,
+            ask_asset_info: swap.ask_asset_info.to_raw(deps.api)?,
+            balance_before,
+            minimum_receive: swap.minimum_receive,
+            remaining_swaps: swap_messages.len() as u32,
+        },
+    )?;
+
+    Ok(swap_messages)
+}
+
+/// Returns the whitelisted pair which trades `offer_asset_info` for `ask_asset_info`
+fn find_swap_pair(
+    deps: Deps,
+    offer_asset_info: &AssetInfo,
+    ask_asset_info: &AssetInfo,
+) -> Result<Addr, ContractError> {
+    let offer_asset_info_raw = offer_asset_info.to_raw(deps.api)?;
+    let ask_asset_info_raw = ask_asset_info.to_raw(deps.api)?;
+    for item in SWAP_PAIRS.range(deps.storage, None, None, Order::Ascending) {
+        let (pair_raw, asset_infos) = item?;
+        if asset_infos
+            .iter()
+            .any(|asset_info| asset_info.equal(&offer_asset_info_raw))
+            && asset_infos
+                .iter()
+                .any(|asset_info| asset_info.equal(&ask_asset_info_raw))
+        {
+            return Ok(deps.api.addr_humanize(&CanonicalAddr::from(pair_raw))?);
+        }
+    }
+
+    Err(ContractError::NoSwapPair {
+        offer: offer_asset_info.to_string(),
+        ask: ask_asset_info.to_string(),
+    })
+}
+
+fn swap_msg(pair: Addr, offer_asset: Asset, max_spread: Decimal) -> StdResult<CosmosMsg> {
+    match &offer_asset.info {
+        AssetInfo::NativeToken { denom } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: pair.to_string(),
+            funds: vec![Coin {
+                denom: denom.to_string(),
+                amount: offer_asset.amount,
+            }],
+            msg: to_binary(&PairExecuteMsg::Swap {
+                offer_asset: offer_asset.clone(),
+                belief_price: None,
+                max_spread: Some(max_spread),
+                to: None,
+            })?,
+        })),
+        AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: pair.to_string(),
+                amount: offer_asset.amount,
+                msg: to_binary(&PairCw20HookMsg::Swap {
+                    belief_price: None,
+                    max_spread: Some(max_spread),
+                    to: None,
+                })?,
+            })?,
+        })),
+    }
+}
+
+/// Reply of a failed stake changed hook of an emergency withdrawal. The state changes
+/// of the hook are reverted while the withdrawal is kept.
+pub fn ignore_hook_failure(msg: Reply) -> Result<Response, ContractError> {
+    let error = match msg.result {
+        ContractResult::Err(error) => error,
+        ContractResult::Ok(_) => String::new(),
+    };
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "ignore_hook_failure"),
+        attr("error", error),
+    ]))
+}
+
+/// Forwards the amounts received by the swaps to the recipient after the last swap
+pub fn complete_claim_swap(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let mut pending_swap = PENDING_SWAP
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingSwap {})?;
+    pending_swap.remaining_swaps =
+        pending_swap.remaining_swaps.checked_sub(1).ok_or_else(|| {
+            OverflowError::new(OverflowOperation::Sub, pending_swap.remaining_swaps, 1)
+        })?;
+    if pending_swap.remaining_swaps > 0 {
+        PENDING_SWAP.save(deps.storage, &pending_swap)?;
+        return Ok(Response::new());
+    }
+    PENDING_SWAP.remove(deps.storage);
+
+    let ask_asset_info = pending_swap.ask_asset_info.to_normal(deps.api)?;
+    let received = ask_asset_info
+        .query_pool(&deps.querier, deps.api, env.contract.address)?
+        .checked_sub(pending_swap.balance_before)?;
+    if received < pending_swap.minimum_receive {
+        return Err(ContractError::SwapSlippageExceeded {
+            received,
+            minimum_receive: pending_swap.minimum_receive,
+        });
+    }
+
+    let recipient = deps.api.addr_humanize(&pending_swap.recipient)?;
+    let asset = Asset {
+        info: ask_asset_info,
+        amount: received,
+    };
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "claim_swap"),
+            attr("recipient", recipient.to_string()),
+            attr("amount", asset.to_string()),
+        ])
+        .add_message(asset.into_msg(&deps.querier, recipient)?))
+}
+
+pub fn emergency_withdraw(
+    deps: DepsMut,
+    env: Env,
+    user: String,
+    receipt_amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = CONFIGURATION.load(deps.storage)?;
+    assert_not_paused(config.paused.withdraw, "withdraw")?;
+    let mut state = STATE.load(deps.storage)?;
+    update_rewards(deps.storage, &env, &mut state)?;
+    let user_raw = deps.api.addr_canonicalize(&user)?;
+    let mut user_info = USER_INFO
+        .load(deps.storage, user_raw.as_slice())
+        .unwrap_or_default();
+    if user_info.stake_amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    if config.receipt_token.is_some() {
+        match receipt_amount {
+            None => return Err(ContractError::ReceiptsRequired {}),
+            Some(received) if received != user_info.stake_amount => {
+                return Err(ContractError::ReceiptAmountMismatch {
+                    received,
+                    stake: user_info.stake_amount,
+                })
+            }
+            _ => {}
+        }
+    }
+    settle_rewards(&state, &mut user_info)?;
+
+    state.total_deposits = state.total_deposits.checked_sub(user_info.stake_amount)?;
+    state.total_weight = state.total_weight.checked_sub(user_info.weight)?;
+
+    let mut forfeited_rewards: Vec<Asset> = vec![];
+    for (reward_index, (asset_info, user_reward)) in config
+        .reward_asset_infos
+        .iter()
+        .zip(user_info.rewards.iter())
+        .enumerate()
+    {
+        if user_reward.pending_amount.is_zero() {
+            continue;
+        }
+
+        let reward = &mut state.rewards[reward_index];
+        reward.distributed_amount = reward
+            .distributed_amount
+            .checked_sub(user_reward.pending_amount)?;
+        match config.forfeit_mode {
+            ForfeitMode::Redistribute => {
+                distribute_reward(&mut state, reward_index, user_reward.pending_amount)?
+            }
+            ForfeitMode::SetAside => {
+                reward.forfeited_amount = reward
+                    .forfeited_amount
+                    .checked_add(user_reward.pending_amount)?
+            }
+        }
+        forfeited_rewards.push(Asset {
+            info: asset_info.to_normal(deps.api)?,
+            amount: user_reward.pending_amount,
+        });
+    }
+
+    // locked stake is unbonded until the end of its lock, and the unlocked stake
+    // is only returned right away if there is no unbonding period
+    let now = env.block.time.seconds();
+    let release_time = add_seconds(now, config.unbonding_period)?;
+    release_expired_locks(&mut user_info, now);
+    let mut unbonds = UNBONDS
+        .may_load(deps.storage, user_raw.as_slice())?
+        .unwrap_or_default();
+    for lock in user_info.locks.iter() {
+        unbonds.push(Unbond {
+            amount: lock.amount,
+            release_time: lock.end_time.max(release_time),
+            recipient: user.clone(),
+        });
+    }
+    let mut unlocked_amount = compute_unlocked_amount(&user_info)?;
+    if config.unbonding_period > 0 && !unlocked_amount.is_zero() {
+        unbonds.push(Unbond {
+            amount: unlocked_amount,
+            release_time,
+            recipient: user.clone(),
+        });
+        unlocked_amount = Uint128::zero();
+    }
+    let unbonding_amount = user_info.stake_amount.checked_sub(unlocked_amount)?;
+    if !unbonding_amount.is_zero() {
+        state.total_unbonding = state.total_unbonding.checked_add(unbonding_amount)?;
+        UNBONDS.save(deps.storage, user_raw.as_slice(), &unbonds)?;
+    }
+
+    update_staker_count(&mut state, user_info.stake_amount, Uint128::zero())?;
+    snapshot_stakes(
+        deps.storage,
+        &user_raw,
+        Uint128::zero(),
+        state.total_deposits,
+        env.block.height,
+    )?;
+    USER_INFO.remove(deps.storage, user_raw.as_slice());
+    STATE.save(deps.storage, &state)?;
+
+    let forfeited_amount = forfeited_rewards
+        .iter()
+        .map(|asset| asset.to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+    let receipt_messages = receipt_token_messages(
+        deps.as_ref(),
+        &config,
+        &user,
+        Uint128::zero(),
+        user_info.stake_amount,
+    )?;
+    // a failing hook must not block the withdrawal, so its failure is ignored in the reply
+    let hook_messages = stake_changed_hook_messages(
+        deps.as_ref(),
+        &user,
+        user_info.stake_amount,
+        Uint128::zero(),
+    )?
+    .into_iter()
+    .map(|sub_msg| SubMsg::reply_on_error(sub_msg.msg, STAKE_CHANGED_HOOK_REPLY_ID))
+    .collect::<Vec<_>>();
+
+    let mut response = Response::new().add_attributes(vec![
+        attr("action", "emergency_withdraw"),
+        attr("amount", user_info.stake_amount),
+        attr("forfeited_amount", forfeited_amount),
+    ]);
+    if !unbonding_amount.is_zero() {
+        response = response.add_attribute("unbonding_amount", unbonding_amount);
+    }
+    if !unlocked_amount.is_zero() {
+        let asset: Asset = Asset {
+            info: config.staking_asset_info.to_normal(deps.api)?,
+            amount: unlocked_amount,
+        };
+        response = response.add_message(asset.into_msg(&deps.querier, Addr::unchecked(user))?);
+    }
+
+    Ok(response
+        .add_messages(receipt_messages)
+        .add_submessages(hook_messages))
+}
+
+pub fn compound(deps: DepsMut, env: Env, user: String) -> Result<Response, ContractError> {
+    let config = CONFIGURATION.load(deps.storage)?;
+    assert_not_paused(config.paused.claim, "claim")?;
+    assert_not_paused(config.paused.deposit, "deposit")?;
+    let reward_index =
+        get_staking_reward_index(&config).ok_or(ContractError::StakingTokenNotRewarded {})?;
+
+    let mut state = STATE.load(deps.storage)?;
+    update_rewards(deps.storage, &env, &mut state)?;
+    let user_raw = deps.api.addr_canonicalize(&user)?;
+    let mut user_info = USER_INFO
+        .load(deps.storage, user_raw.as_slice())
+        .unwrap_or_default();
+    let prev_stake_amount = user_info.stake_amount;
+    settle_rewards(&state, &mut user_info)?;
+    release_expired_locks(&mut user_info, env.block.time.seconds());
+    let amount = compound_rewards(&mut state, &mut user_info, reward_index)?;
+    if amount.is_zero() {
+        return Err(ContractError::ZeroReward {});
+    }
+    update_weight(&mut state, &mut user_info)?;
+    reset_reward_debts(&state, &mut user_info)?;
+
+    update_staker_count(&mut state, prev_stake_amount, user_info.stake_amount)?;
+    save_user_info(deps.storage, &user_raw, &user_info)?;
+    snapshot_stakes(
+        deps.storage,
+        &user_raw,
+        user_info.stake_amount,
+        state.total_deposits,
+        env.block.height,
+    )?;
+    STATE.save(deps.storage, &state)?;
+
+    let receipt_messages = receipt_token_messages(
+        deps.as_ref(),
+        &config,
+        &user,
+        user_info.stake_amount.checked_sub(prev_stake_amount)?,
+        Uint128::zero(),
+    )?;
+    let hook_messages = stake_changed_hook_messages(
+        deps.as_ref(),
+        &user,
+        prev_stake_amount,
+        user_info.stake_amount,
+    )?;
+
+    Ok(Response::new()
+        .add_attributes(vec![attr("action", "compound"), attr("amount", amount)])
+        .add_messages(receipt_messages)
+        .add_submessages(hook_messages))
+}
+
+pub fn set_auto_compound(
+    deps: DepsMut,
+    user: String,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    let user_raw = deps.api.addr_canonicalize(&user)?;
+    let mut user_info = USER_INFO
+        .may_load(deps.storage, user_raw.as_slice())?
+        .unwrap_or_default();
+    if user_info.stake_amount.is_zero() {
+        return Err(ContractError::NoStake {});
+    }
+    user_info.auto_compound = enabled;
+    USER_INFO.save(deps.storage, user_raw.as_slice(), &user_info)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_auto_compound"),
+        attr("enabled", enabled.to_string()),
+    ]))
+}
+
+pub fn approve(
+    deps: DepsMut,
+    env: Env,
+    owner: String,
+    operator: String,
+    can_withdraw: bool,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let owner_raw = deps.api.addr_canonicalize(&owner)?;
+    let operator_raw = deps.api.addr_canonicalize(&operator)?;
+    if owner_raw == operator_raw {
+        return Err(ContractError::CannotApproveSelf {});
+    }
+
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::ApprovalExpired {});
+    }
+
+    // the stake of a receipt pool is only withdrawn with the receipts,
+    // which an operator can not send for the owner
+    if can_withdraw && CONFIGURATION.load(deps.storage)?.receipt_token.is_some() {
+        return Err(ContractError::ReceiptsRequired {});
+    }
+
+    APPROVALS.save(
+        deps.storage,
+        (owner_raw.as_slice(), operator_raw.as_slice()),
+        &OperatorApproval {
+            can_withdraw,
+            expires,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "approve"),
+        attr("owner", owner),
+        attr("operator", operator),
+        attr("can_withdraw", can_withdraw.to_string()),
+        attr("expires", expires.to_string()),
+    ]))
+}
+
+pub fn revoke(deps: DepsMut, owner: String, operator: String) -> Result<Response, ContractError> {
+    APPROVALS.remove(
+        deps.storage,
+        (
+            deps.api.addr_canonicalize(&owner)?.as_slice(),
+            deps.api.addr_canonicalize(&operator)?.as_slice(),
+        ),
+    );
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "revoke"),
+        attr("owner", owner),
+        attr("operator", operator),
+    ]))
+}
+
+/// Claims the rewards of `user` to the approved `operator`
+pub fn claim_for(
+    deps: DepsMut,
+    env: Env,
+    operator: String,
+    user: String,
+) -> Result<Response, ContractError> {
+    assert_approved(deps.as_ref(), &env, &user, &operator, false)?;
+    claim(deps, env, user, operator, None)
+}
+
+/// Withdraws the stake of `user` to the approved `operator`
+pub fn withdraw_for(
+    deps: DepsMut,
+    env: Env,
+    operator: String,
+    user: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    assert_approved(deps.as_ref(), &env, &user, &operator, true)?;
+    withdraw(deps, env, user, amount, operator, false)
+}
+
+pub fn fund(
+    mut deps: DepsMut,
+    env: Env,
+    funder: String,
+    reward_index: usize,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIGURATION.load(deps.storage)?;
+    assert_not_paused(config.paused.fund, "fund")?;
+
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let funder_raw = deps.api.addr_canonicalize(&funder)?;
+    authorize_funding(
+        deps.branch(),
+        &config,
+        &funder_raw,
+        reward_index,
+        amount,
+        env.block.time.seconds(),
+        true,
+    )?;
+    save_fund_record(
+        deps.storage,
+        FundRecordRaw {
+            id: 0,
+            funder: funder_raw,
+            reward_index: reward_index as u32,
+            amount,
+            time: env.block.time.seconds(),
+            start_time: None,
+            end_time: None,
+        },
+    )?;
+
+    let tax_amount = compute_reserved_tax(
+        &deps.querier,
+        &Asset {
+            info: config.reward_asset_infos[reward_index].to_normal(deps.api)?,
+            amount,
+        },
+    )?;
+
+    let mut state = STATE.load(deps.storage)?;
+    update_rewards(deps.storage, &env, &mut state)?;
+    let reward = &mut state.rewards[reward_index];
+    reward.tax_reserve = reward.tax_reserve.checked_add(tax_amount)?;
+    distribute_reward(&mut state, reward_index, amount.checked_sub(tax_amount)?)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "fund"),
+        attr("funder", funder),
+        attr("amount", amount),
+        attr("tax_amount", tax_amount),
+    ]))
+}
+
+pub fn fund_stream(
+    mut deps: DepsMut,
+    env: Env,
+    funder: String,
+    reward_index: usize,
+    amount: Uint128,
+    start_time: u64,
+    end_time: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIGURATION.load(deps.storage)?;
+    assert_not_paused(config.paused.fund, "fund")?;
+
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let funder_raw = deps.api.addr_canonicalize(&funder)?;
+    authorize_funding(
+        deps.branch(),
+        &config,
+        &funder_raw,
+        reward_index,
+        amount,
+        env.block.time.seconds(),
+        // every handler scans the schedules, so only enabled funders can add them
+        false,
+    )?;
+
+    if start_time < env.block.time.seconds() || end_time <= start_time {
+        return Err(ContractError::InvalidSchedule {
+            start_time,
+            end_time,
+        });
+    }
+
+    save_fund_record(
+        deps.storage,
+        FundRecordRaw {
+            id: 0,
+            funder: funder_raw,
+            reward_index: reward_index as u32,
+            amount,
+            time: env.block.time.seconds(),
+            start_time: Some(start_time),
+            end_time: Some(end_time),
+        },
+    )?;
+
+    let tax_amount = compute_reserved_tax(
+        &deps.querier,
+        &Asset {
+            info: config.reward_asset_infos[reward_index].to_normal(deps.api)?,
+            amount,
+        },
+    )?;
+
+    let mut state = STATE.load(deps.storage)?;
+    update_rewards(deps.storage, &env, &mut state)?;
+    let reward = &mut state.rewards[reward_index];
+    reward.tax_reserve = reward.tax_reserve.checked_add(tax_amount)?;
+    STATE.save(deps.storage, &state)?;
+
+    let mut schedules = REWARD_SCHEDULES.load(deps.storage)?;
+    schedules.push(RewardSchedule {
+        reward_index: reward_index as u32,
+        start_time,
+        end_time,
+        amount: amount.checked_sub(tax_amount)?,
+        released_amount: Uint128::zero(),
+    });
+    REWARD_SCHEDULES.save(deps.storage, &schedules)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "fund_stream"),
+        attr("funder", funder),
+        attr("amount", amount),
+        attr("tax_amount", tax_amount),
+        attr("start_time", start_time.to_string()),
+        attr("end_time", end_time.to_string()),
+    ]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_config(
+    deps: DepsMut,
+    sender: String,
+    guardian: Option<String>,
+    unbonding_period: Option<u64>,
+    forfeit_mode: Option<ForfeitMode>,
+    permissionless_funding: Option<bool>,
+    min_fund_amounts: Option<Vec<Asset>>,
+    cw4_enabled: Option<bool>,
+    tokens_per_weight: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIGURATION.load(deps.storage)?;
+
+    if deps.api.addr_humanize(&config.governance)? != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(guardian) = guardian {
+        config.guardian = Some(deps.api.addr_canonicalize(&guardian)?);
+    }
+
+    if let Some(unbonding_period) = unbonding_period {
+        config.unbonding_period = unbonding_period;
+    }
+
+    if let Some(forfeit_mode) = forfeit_mode {
+        config.forfeit_mode = forfeit_mode;
+    }
+
+    if let Some(permissionless_funding) = permissionless_funding {
+        config.permissionless_funding = permissionless_funding;
+    }
+
+    if let Some(min_fund_amounts) = min_fund_amounts {
+        config.min_fund_amounts =
+            to_min_fund_amounts(deps.api, &config.reward_asset_infos, &min_fund_amounts)?;
+    }
+
+    if let Some(cw4_enabled) = cw4_enabled {
+        config.cw4_enabled = cw4_enabled;
+    }
+
+    if let Some(tokens_per_weight) = tokens_per_weight {
+        if tokens_per_weight.is_zero() {
+            return Err(ContractError::InvalidTokensPerWeight {});
+        }
+        config.tokens_per_weight = tokens_per_weight;
+    }
+
+    assert_min_fund_amounts(deps.api, &config)?;
+    CONFIGURATION.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+pub fn propose_governance(
+    deps: DepsMut,
+    env: Env,
+    sender: String,
+    governance: String,
+    expires_in: u64,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIGURATION.load(deps.storage)?;
+
+    if deps.api.addr_humanize(&config.governance)? != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let expires_at = add_seconds(env.block.time.seconds(), expires_in)?;
+    config.pending_governance = Some(PendingHandoverRaw {
+        address: deps.api.addr_canonicalize(&governance)?,
+        expires_at,
+    });
+    CONFIGURATION.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_governance"),
+        attr("governance", governance),
+        attr("expires_at", expires_at.to_string()),
+    ]))
+}
+
+pub fn accept_governance(
+    deps: DepsMut,
+    env: Env,
+    sender: String,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIGURATION.load(deps.storage)?;
+
+    let sender_raw = deps.api.addr_canonicalize(&sender)?;
+    assert_handover_acceptable(
+        &config.pending_governance,
+        &sender_raw,
+        env.block.time.seconds(),
+    )?;
+
+    config.governance = sender_raw;
+    config.pending_governance = None;
+    CONFIGURATION.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "accept_governance"),
+        attr("governance", sender),
+    ]))
+}
+
+pub fn cancel_governance_proposal(
+    deps: DepsMut,
+    sender: String,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIGURATION.load(deps.storage)?;
+
+    if deps.api.addr_humanize(&config.governance)? != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if config.pending_governance.take().is_none() {
+        return Err(ContractError::NoPendingHandover {});
+    }
+    CONFIGURATION.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "cancel_governance_proposal"))
+}
+
+pub fn update_funder(
+    deps: DepsMut,
+    sender: String,
+    funder: String,
+    enabled: bool,
+    caps: Vec<FundingCap>,
+) -> Result<Response, ContractError> {
+    let config = CONFIGURATION.load(deps.storage)?;
+
+    if deps.api.addr_humanize(&config.governance)? != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let funder_raw = deps
+        .api
+        .addr_canonicalize(deps.api.addr_validate(&funder)?.as_str())?;
+    let mut funder_info = FUNDERS
+        .may_load(deps.storage, funder_raw.as_slice())?
+        .unwrap_or_default();
+    funder_info.enabled = enabled;
+    funder_info
+        .rewards
+        .resize(config.reward_asset_infos.len(), FunderReward::default());
+    for funder_reward in funder_info.rewards.iter_mut() {
+        funder_reward.cap_amount = None;
+        funder_reward.cap_period = 0;
+    }
+
+    for cap in caps {
+        let asset_info_raw = cap.asset_info.to_raw(deps.api)?;
+        let reward_index = config
+            .reward_asset_infos
+            .iter()
+            .position(|reward_asset_info| reward_asset_info.equal(&asset_info_raw))
+            .ok_or_else(|| ContractError::UnsupportedRewardAsset {
+                asset_info: cap.asset_info.to_string(),
+            })?;
+        if cap.period == 0 {
+            return Err(ContractError::InvalidFundingCap {
+                asset_info: cap.asset_info.to_string(),
+            });
+        }
+
+        let funder_reward = &mut funder_info.rewards[reward_index];
+        funder_reward.cap_amount = Some(cap.amount);
+        funder_reward.cap_period = cap.period;
+    }
+    FUNDERS.save(deps.storage, funder_raw.as_slice(), &funder_info)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_funder"),
+        attr("funder", funder),
+        attr("enabled", enabled.to_string()),
+    ]))
+}
+
+pub fn add_reward_asset(
+    deps: DepsMut,
+    sender: String,
+    asset_info: AssetInfo,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIGURATION.load(deps.storage)?;
+
+    if deps.api.addr_humanize(&config.governance)? != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let asset_info_raw = asset_info.to_raw(deps.api)?;
+    if config
+        .reward_asset_infos
+        .iter()
+        .any(|reward_asset_info| reward_asset_info.equal(&asset_info_raw))
+    {
+        return Err(ContractError::DuplicateRewardAsset {
+            asset_info: asset_info.to_string(),
+        });
+    }
+    config.reward_asset_infos.push(asset_info_raw);
+    CONFIGURATION.save(deps.storage, &config)?;
+
+    let mut state = STATE.load(deps.storage)?;
+    state.rewards.push(RewardState::default());
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "add_reward_asset"),
+        attr("asset_info", asset_info.to_string()),
+    ]))
+}
+
+pub fn add_swap_pair(
+    deps: DepsMut,
+    sender: String,
+    pair: String,
+    asset_infos: [AssetInfo; 2],
+) -> Result<Response, ContractError> {
+    let config = CONFIGURATION.load(deps.storage)?;
+
+    if deps.api.addr_humanize(&config.governance)? != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    SWAP_PAIRS.save(
+        deps.storage,
+        deps.api.addr_canonicalize(&pair)?.as_slice(),
+        &[
+            asset_infos[0].to_raw(deps.api)?,
+            asset_infos[1].to_raw(deps.api)?,
+        ],
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "add_swap_pair"),
+        attr("pair", pair),
+        attr(
+            "asset_infos",
+            format!("{},{}", asset_infos[0], asset_infos[1]),
+        ),
+    ]))
+}
+
+pub fn remove_swap_pair(
+    deps: DepsMut,
+    sender: String,
+    pair: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIGURATION.load(deps.storage)?;
+
+    if deps.api.addr_humanize(&config.governance)? != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    SWAP_PAIRS.remove(deps.storage, deps.api.addr_canonicalize(&pair)?.as_slice());
+
+    Ok(
+        Response::new()
+            .add_attributes(vec![attr("action", "remove_swap_pair"), attr("pair", pair)]),
+    )
+}
+
+pub fn add_hook(deps: DepsMut, sender: String, addr: String) -> Result<Response, ContractError> {
+    let config = CONFIGURATION.load(deps.storage)?;
+
+    if deps.api.addr_humanize(&config.governance)? != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let addr_raw = deps.api.addr_canonicalize(&addr)?;
+    let mut hooks = HOOKS.may_load(deps.storage)?.unwrap_or_default();
+    if hooks.contains(&addr_raw) {
+        return Err(ContractError::HookAlreadyRegistered { addr });
+    }
+    hooks.push(addr_raw);
+    HOOKS.save(deps.storage, &hooks)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "add_hook"), attr("addr", addr)]))
+}
+
+pub fn remove_hook(deps: DepsMut, sender: String, addr: String) -> Result<Response, ContractError> {
+    let config = CONFIGURATION.load(deps.storage)?;
+
+    if deps.api.addr_humanize(&config.governance)? != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let addr_raw = deps.api.addr_canonicalize(&addr)?;
+    let mut hooks = HOOKS.may_load(deps.storage)?.unwrap_or_default();
+    if !hooks.contains(&addr_raw) {
+        return Err(ContractError::HookNotRegistered { addr });
+    }
+    hooks.retain(|hook| *hook != addr_raw);
+    HOOKS.save(deps.storage, &hooks)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "remove_hook"), attr("addr", addr)]))
+}
+
+/// Stores the address of the receipt token instantiated by the pool
+pub fn register_receipt_token(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    let events = msg
+        .result
+        .into_result()
+        .map_err(|_| ContractError::ReceiptTokenNotFound {})?
+        .events;
+    let receipt_token = events
+        .iter()
+        .filter(|event| event.ty == "instantiate_contract")
+        .flat_map(|event| event.attributes.iter())
+        .find(|attribute| attribute.key == "contract_address")
+        .map(|attribute| attribute.value.clone())
+        .ok_or(ContractError::ReceiptTokenNotFound {})?;
+
+    let mut config = CONFIGURATION.load(deps.storage)?;
+    config.receipt_token = Some(deps.api.addr_canonicalize(&receipt_token)?);
+    CONFIGURATION.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "register_receipt_token"),
+        attr("receipt_token", receipt_token),
+    ]))
+}
+
+/// Moves the stake of the transferred receipts from `sender` to `recipient`
+/// and settles the rewards of both, compounding them if auto compounding is enabled.
+/// Locked stake can not be transferred, so a receipt transfer beyond the unlocked
+/// stake of the sender fails.
+pub fn receipt_transfer_hook(
+    deps: DepsMut,
+    env: Env,
+    caller: String,
+    msg: ReceiptTransferHookMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIGURATION.load(deps.storage)?;
+    if !is_receipt_token(deps.api, &config, &caller)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if msg.amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    // receipts sent to the pool are withdrawn by the following receive,
+    // so their stake stays with the sender until then
+    if msg.recipient == env.contract.address.as_str() {
+        return Ok(Response::new().add_attributes(vec![
+            attr("action", "receipt_return"),
+            attr("sender", msg.sender),
+            attr("amount", msg.amount),
+        ]));
+    }
+
+    let mut state = STATE.load(deps.storage)?;
+    update_rewards(deps.storage, &env, &mut state)?;
+
+    let sender_raw = deps.api.addr_canonicalize(&msg.sender)?;
+    let mut sender_info = USER_INFO
+        .load(deps.storage, sender_raw.as_slice())
+        .unwrap_or_default();
+    let prev_sender_stake = sender_info.stake_amount;
+    settle_rewards(&state, &mut sender_info)?;
+    let sender_compounded_amount = auto_compound(&config, &mut state, &mut sender_info)?;
+    release_expired_locks(&mut sender_info, env.block.time.seconds());
+    let unlocked_amount = compute_unlocked_amount(&sender_info)?;
+    if msg.amount > unlocked_amount {
+        return Err(ContractError::InsufficientStake {
+            requested: msg.amount,
+            available: unlocked_amount,
+        });
+    }
+    sender_info.stake_amount = sender_info.stake_amount.checked_sub(msg.amount)?;
+    update_weight(&mut state, &mut sender_info)?;
+    reset_reward_debts(&state, &mut sender_info)?;
+
+    update_staker_count(&mut state, prev_sender_stake, sender_info.stake_amount)?;
+    save_user_info(deps.storage, &sender_raw, &sender_info)?;
+    snapshot_stakes(
+        deps.storage,
+        &sender_raw,
+        sender_info.stake_amount,
+        state.total_deposits,
+        env.block.height,
+    )?;
+
+    let recipient_raw = deps.api.addr_canonicalize(&msg.recipient)?;
+    let mut recipient_info = USER_INFO
+        .load(deps.storage, recipient_raw.as_slice())
+        .unwrap_or_default();
+    let prev_recipient_stake = recipient_info.stake_amount;
+    settle_rewards(&state, &mut recipient_info)?;
+    let recipient_compounded_amount = auto_compound(&config, &mut state, &mut recipient_info)?;
+    release_expired_locks(&mut recipient_info, env.block.time.seconds());
+    recipient_info.stake_amount = recipient_info.stake_amount.checked_add(msg.amount)?;
+    update_weight(&mut state, &mut recipient_info)?;
+    reset_reward_debts(&state, &mut recipient_info)?;
+
+    update_staker_count(
+        &mut state,
+        prev_recipient_stake,
+        recipient_info.stake_amount,
+    )?;
+    save_user_info(deps.storage, &recipient_raw, &recipient_info)?;
+    snapshot_stakes(
+        deps.storage,
+        &recipient_raw,
+        recipient_info.stake_amount,
+        state.total_deposits,
+        env.block.height,
+    )?;
+    STATE.save(deps.storage, &state)?;
+
+    let mut receipt_messages = receipt_token_messages(
+        deps.as_ref(),
+        &config,
+        &msg.sender,
+        sender_compounded_amount,
+        Uint128::zero(),
+    )?;
+    receipt_messages.extend(receipt_token_messages(
+        deps.as_ref(),
+        &config,
+        &msg.recipient,
+        recipient_compounded_amount,
+        Uint128::zero(),
+    )?);
+    let mut hook_messages = stake_changed_hook_messages(
+        deps.as_ref(),
+        &msg.sender,
+        prev_sender_stake,
+        sender_info.stake_amount,
+    )?;
+    hook_messages.extend(stake_changed_hook_messages(
+        deps.as_ref(),
+        &msg.recipient,
+        prev_recipient_stake,
+        recipient_info.stake_amount,
+    )?);
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "receipt_transfer"),
+            attr("sender", msg.sender),
+            attr("recipient", msg.recipient),
+            attr("amount", msg.amount),
+        ])
+        .add_messages(receipt_messages)
+        .add_submessages(hook_messages))
+}
+
+pub fn sweep_forfeited_rewards(
+    deps: DepsMut,
+    sender: String,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIGURATION.load(deps.storage)?;
+
+    if deps.api.addr_humanize(&config.governance)? != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let mut state = STATE.load(deps.storage)?;
+    let mut forfeited_rewards: Vec<Asset> = vec![];
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for (asset_info, reward) in config
+        .reward_asset_infos
+        .iter()
+        .zip(state.rewards.iter_mut())
+    {
+        if !reward.forfeited_amount.is_zero() {
+            let asset = Asset {
+                info: asset_info.to_normal(deps.api)?,
+                amount: std::mem::take(&mut reward.forfeited_amount),
+            };
+            messages.push(into_reward_msg(
+                &deps.querier,
+                reward,
+                asset.clone(),
+                recipient.clone(),
+            )?);
+            forfeited_rewards.push(asset);
+        }
+    }
+    STATE.save(deps.storage, &state)?;
+
+    if forfeited_rewards.is_empty() {
+        return Err(ContractError::ZeroReward {});
+    }
+
+    let amount = forfeited_rewards
+        .iter()
+        .map(|asset| asset.to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "sweep_forfeited_rewards"),
+            attr("recipient", recipient),
+            attr("amount", amount),
+        ])
+        .add_messages(messages))
+}
+
+pub fn distribute_tax_reserve(
+    deps: DepsMut,
+    env: Env,
+    sender: String,
+    asset_info: AssetInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIGURATION.load(deps.storage)?;
+
+    if deps.api.addr_humanize(&config.governance)? != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let asset_info_raw = asset_info.to_raw(deps.api)?;
+    let reward_index = config
+        .reward_asset_infos
+        .iter()
+        .position(|reward_asset_info| reward_asset_info.equal(&asset_info_raw))
+        .ok_or_else(|| ContractError::UnsupportedRewardAsset {
+            asset_info: asset_info.to_string(),
+        })?;
+
+    let mut state = STATE.load(deps.storage)?;
+    update_rewards(deps.storage, &env, &mut state)?;
+    let reward = &mut state.rewards[reward_index];
+    if amount > reward.tax_reserve {
+        return Err(ContractError::InsufficientTaxReserve {
+            requested: amount,
+            available: reward.tax_reserve,
+        });
+    }
+    reward.tax_reserve = reward.tax_reserve.checked_sub(amount)?;
+    distribute_reward(&mut state, reward_index, amount)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "distribute_tax_reserve"),
+        attr("asset_info", asset_info.to_string()),
+        attr("amount", amount),
+    ]))
+}
+
+pub fn set_paused(
+    deps: DepsMut,
+    sender: String,
+    deposit: Option<bool>,
+    fund: Option<bool>,
+    claim: Option<bool>,
+    withdraw: Option<bool>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIGURATION.load(deps.storage)?;
+
+    let sender_raw = deps.api.addr_canonicalize(&sender)?;
+    if sender_raw != config.governance {
+        let unpause = [deposit, fund, claim, withdraw].contains(&Some(false));
+        if config.guardian != Some(sender_raw) || unpause {
+            return Err(ContractError::Unauthorized {});
+        }
+    }
+
+    if let Some(deposit) = deposit {
+        config.paused.deposit = deposit;
+    }
+
+    if let Some(fund) = fund {
+        config.paused.fund = fund;
+    }
+
+    if let Some(claim) = claim {
+        config.paused.claim = claim;
+    }
+
+    if let Some(withdraw) = withdraw {
+        config.paused.withdraw = withdraw;
+    }
+
+    CONFIGURATION.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_paused"),
+        attr("deposit", config.paused.deposit.to_string()),
+        attr("fund", config.paused.fund.to_string()),
+        attr("claim", config.paused.claim.to_string()),
+        attr("withdraw", config.paused.withdraw.to_string()),
+    ]))
+}
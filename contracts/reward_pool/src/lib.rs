@@ -1,8 +1,10 @@
-pub mod contract;
-mod execute;
-mod query;
-mod state;
-mod utils;
-
-#[cfg(test)]
-mod tests;
+pub mod contract;
+pub mod error;
+mod execute;
+mod migration;
+mod query;
+mod state;
+mod utils;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,233 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{
+    CanonicalAddr, Decimal, Decimal256, Fraction, Order, StdResult, Storage, Uint128,
+};
+use cw_storage_plus::{Bound, Item, Map};
+
+use reward_pool::reward_pool::{ForfeitMode, PauseFlags, RewardState, State, UserReward};
+use terraswap::asset::AssetInfoRaw;
+
+use crate::{
+    error::ContractError,
+    state::{
+        Config, FunderInfo, UserInfo, CONFIGURATION, FUNDERS, REWARD_SCHEDULES, STATE, TOTAL_STAKE,
+        TOTAL_STAKE_KEY, USER_INFO, USER_STAKES,
+    },
+    utils::{compute_reward, save_user_info, update_staker_count},
+};
+
+/// Config of the single reward asset pool, stored under the same key as `Config`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
+    pub governance: CanonicalAddr,
+    pub funder: CanonicalAddr,
+    pub staking_token: CanonicalAddr,
+    pub reward_asset_info: AssetInfoRaw,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct LegacyState {
+    pub acc_per_share: Decimal,
+    pub total_deposits: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct LegacyUserInfo {
+    pub stake_amount: Uint128,
+    pub pending_amount: Uint128,
+    pub reward_debt: Uint128,
+}
+
+pub const LEGACY_CONFIGURATION: Item<LegacyConfig> = Item::new("config");
+pub const LEGACY_STATE: Item<LegacyState> = Item::new("state");
+pub const LEGACY_USER_INFO: Map<&[u8], LegacyUserInfo> = Map::new("user_infos");
+
+/// Conversion of the users of a single reward asset pool which is not completed yet.
+/// Users are converted in the order of their keys, after `last_user` if set.
+/// Every execute msg except `MigrateUsers` fails until the conversion is completed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyMigration {
+    pub acc_per_share: Decimal,
+    pub last_user: Option<CanonicalAddr>,
+}
+
+pub const LEGACY_MIGRATION: Item<LegacyMigration> = Item::new("legacy_migration");
+
+/// Number of users converted by `migrate` or by `MigrateUsers` without a limit
+pub const DEFAULT_MIGRATION_LIMIT: u32 = 30;
+pub const MAX_MIGRATION_LIMIT: u32 = 100;
+
+/// Converts the config and state of a single reward asset pool in place and starts
+/// the conversion of its users. Returns false if the storage is already in the current layout.
+/// The pending rewards of the users are settled at the old `acc_per_share` on conversion,
+/// so the converted users count into `staker_count` and the distributed rewards from then on.
+pub fn migrate_legacy_storage(storage: &mut dyn Storage, height: u64) -> StdResult<bool> {
+    let legacy_config = match LEGACY_CONFIGURATION.load(storage) {
+        Ok(legacy_config) => legacy_config,
+        Err(_) => return Ok(false),
+    };
+    let legacy_state = LEGACY_STATE.load(storage)?;
+
+    STATE.save(
+        storage,
+        &State {
+            total_deposits: legacy_state.total_deposits,
+            total_weight: legacy_state.total_deposits,
+            total_unbonding: Uint128::zero(),
+            staker_count: 0,
+            rewards: vec![RewardState {
+                acc_per_share: to_decimal256(legacy_state.acc_per_share),
+                ..RewardState::default()
+            }],
+        },
+    )?;
+    CONFIGURATION.save(
+        storage,
+        &Config {
+            governance: legacy_config.governance,
+            pending_governance: None,
+            guardian: None,
+            staking_asset_info: AssetInfoRaw::Token {
+                contract_addr: legacy_config.staking_token,
+            },
+            reward_asset_infos: vec![legacy_config.reward_asset_info],
+            lock_tiers: vec![],
+            unbonding_period: 0,
+            forfeit_mode: ForfeitMode::Redistribute,
+            permissionless_funding: false,
+            min_fund_amounts: vec![],
+            cw4_enabled: false,
+            tokens_per_weight: Uint128::from(1u128),
+            paused: PauseFlags::default(),
+            receipt_token: None,
+        },
+    )?;
+    REWARD_SCHEDULES.save(storage, &vec![])?;
+    FUNDERS.save(
+        storage,
+        legacy_config.funder.as_slice(),
+        &FunderInfo {
+            enabled: true,
+            rewards: vec![],
+        },
+    )?;
+    LEGACY_MIGRATION.save(
+        storage,
+        &LegacyMigration {
+            acc_per_share: legacy_state.acc_per_share,
+            last_user: None,
+        },
+    )?;
+    // the users are seeded on conversion
+    TOTAL_STAKE.save(
+        storage,
+        TOTAL_STAKE_KEY,
+        &legacy_state.total_deposits,
+        height,
+    )?;
+
+    Ok(true)
+}
+
+/// Converts up to `limit` users of the pending legacy migration and seeds their
+/// stake snapshots at `height`. Returns the number of converted users and whether
+/// the migration is completed.
+pub fn migrate_legacy_users(
+    storage: &mut dyn Storage,
+    height: u64,
+    limit: u32,
+) -> Result<(u64, bool), ContractError> {
+    let mut migration = LEGACY_MIGRATION
+        .may_load(storage)?
+        .ok_or(ContractError::NoPendingMigration {})?;
+    let limit = limit.min(MAX_MIGRATION_LIMIT) as usize;
+    let start = migration
+        .last_user
+        .as_ref()
+        .map(|user_raw| Bound::exclusive(user_raw.as_slice()));
+    // the users after the last converted one are all in the legacy layout
+    let mut legacy_users = LEGACY_USER_INFO
+        .range(storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+    let completed = legacy_users.len() <= limit;
+    legacy_users.truncate(limit);
+
+    let mut state = STATE.load(storage)?;
+    let acc_per_share = to_decimal256(migration.acc_per_share);
+    for (user_raw, legacy_user_info) in legacy_users.iter() {
+        let pending_amount = legacy_user_info.pending_amount.checked_add(
+            (legacy_user_info.stake_amount * migration.acc_per_share)
+                .checked_sub(legacy_user_info.reward_debt)?,
+        )?;
+        // the funded total is not known, so the owed rewards count as distributed
+        let reward = &mut state.rewards[0];
+        reward.distributed_amount = reward.distributed_amount.checked_add(pending_amount)?;
+        update_staker_count(&mut state, Uint128::zero(), legacy_user_info.stake_amount)?;
+
+        save_user_info(
+            storage,
+            &CanonicalAddr::from(user_raw.as_slice()),
+            &UserInfo {
+                stake_amount: legacy_user_info.stake_amount,
+                weight: legacy_user_info.stake_amount,
+                locks: vec![],
+                rewards: vec![UserReward {
+                    pending_amount,
+                    reward_debt: compute_reward(legacy_user_info.stake_amount, acc_per_share)?,
+                }],
+                auto_compound: false,
+            },
+        )?;
+        USER_STAKES.save(
+            storage,
+            user_raw.as_slice(),
+            &legacy_user_info.stake_amount,
+            height,
+        )?;
+    }
+    STATE.save(storage, &state)?;
+
+    if completed {
+        LEGACY_MIGRATION.remove(storage);
+    } else {
+        migration.last_user = legacy_users
+            .last()
+            .map(|(user_raw, _)| CanonicalAddr::from(user_raw.clone()));
+        LEGACY_MIGRATION.save(storage, &migration)?;
+    }
+
+    Ok((legacy_users.len() as u64, completed))
+}
+
+fn to_decimal256(decimal: Decimal) -> Decimal256 {
+    Decimal256::from_ratio(decimal.numerator(), decimal.denominator())
+}
+
+/// Seeds the stake snapshots at `height` for pools whose stakes predate them,
+/// so the stake at any later height is known without a stake change.
+/// Every stake change snapshots the total stake as well, so the snapshots are
+/// only seeded once. Returns the number of seeded users.
+pub fn seed_stake_snapshots(storage: &mut dyn Storage, height: u64) -> StdResult<u64> {
+    if TOTAL_STAKE.may_load(storage, TOTAL_STAKE_KEY)?.is_some() {
+        return Ok(0);
+    }
+
+    let users = USER_INFO
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (user_raw, user_info) in users.iter() {
+        USER_STAKES.save(
+            storage,
+            user_raw.as_slice(),
+            &user_info.stake_amount,
+            height,
+        )?;
+    }
+    let state = STATE.load(storage)?;
+    TOTAL_STAKE.save(storage, TOTAL_STAKE_KEY, &state.total_deposits, height)?;
+
+    Ok(users.len() as u64)
+}
@@ -1,34 +1,584 @@
-use cosmwasm_std::{Deps, StdResult};
-
-use reward_pool::reward_pool::{ConfigResponse, State, UserInfoResponse};
-
-use crate::state::{CONFIGURATION, STATE, USER_INFO};
-
-pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
-    let config = CONFIGURATION.load(deps.storage)?;
-
-    Ok(ConfigResponse {
-        governance: deps.api.addr_humanize(&config.governance)?.to_string(),
-        funder: deps.api.addr_humanize(&config.funder)?.to_string(),
-        staking_token: deps.api.addr_humanize(&config.staking_token)?.to_string(),
-        reward_asset_info: config.reward_asset_info.to_normal(deps.api)?,
-    })
-}
-
-pub fn query_state(deps: Deps) -> StdResult<State> {
-    Ok(STATE.load(deps.storage)?)
-}
-
-pub fn query_user_info(deps: Deps, user: String) -> StdResult<UserInfoResponse> {
-    let user_info = USER_INFO
-        .load(deps.storage, deps.api.addr_canonicalize(&user)?.as_slice())
-        .unwrap_or_default();
-
-    let state = STATE.load(deps.storage)?;
-
-    Ok(UserInfoResponse {
-        stake_amount: user_info.stake_amount,
-        pending_amount: (user_info.stake_amount * state.acc_per_share - user_info.reward_debt)
-            + user_info.pending_amount,
-    })
-}
+use std::convert::TryFrom;
+
+use cosmwasm_std::{CanonicalAddr, Deps, Env, Order, StdError, StdResult, Uint128};
+use cw_storage_plus::{Bound, U64Key};
+
+use reward_pool::reward_pool::{
+    AllUsersResponse, Approval, ApprovalsResponse, ClaimableReward, ClaimableRewardsResponse,
+    ConfigResponse, FundHistoryResponse, FundRecord, FunderResponse, FundersResponse, FundingCap,
+    HooksResponse, LocksResponse, Member, MemberListResponse, MemberResponse,
+    RewardSchedulesResponse, RewardSolvency, SolvencyResponse, StakeAtHeightResponse, State,
+    SwapPair, SwapPairsResponse, TotalWeightResponse, UnbondsResponse, UserInfoResponse,
+    UserResponse,
+};
+use terraswap::asset::Asset;
+
+use crate::{
+    state::{
+        APPROVALS, CONFIGURATION, FUNDERS, FUND_HISTORY, HOOKS, REWARD_SCHEDULES, STATE,
+        SWAP_PAIRS, TOTAL_STAKE, TOTAL_STAKE_KEY, UNBONDS, USER_INFO, USER_STAKES,
+    },
+    utils::{
+        compound_rewards, get_staking_reward_index, pay_reward_tax, release_rewards, settle_rewards,
+    },
+};
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIGURATION.load(deps.storage)?;
+
+    Ok(ConfigResponse {
+        governance: deps.api.addr_humanize(&config.governance)?.to_string(),
+        pending_governance: config
+            .pending_governance
+            .map(|pending_governance| pending_governance.to_normal(deps.api))
+            .transpose()?,
+        guardian: config
+            .guardian
+            .map(|guardian| {
+                deps.api
+                    .addr_humanize(&guardian)
+                    .map(|addr| addr.to_string())
+            })
+            .transpose()?,
+        staking_asset_info: config.staking_asset_info.to_normal(deps.api)?,
+        reward_asset_infos: config
+            .reward_asset_infos
+            .iter()
+            .map(|asset_info| asset_info.to_normal(deps.api))
+            .collect::<StdResult<Vec<_>>>()?,
+        lock_tiers: config.lock_tiers,
+        unbonding_period: config.unbonding_period,
+        forfeit_mode: config.forfeit_mode,
+        permissionless_funding: config.permissionless_funding,
+        min_fund_amounts: config
+            .reward_asset_infos
+            .iter()
+            .zip(config.min_fund_amounts.iter())
+            .filter(|(_, amount)| !amount.is_zero())
+            .map(|(asset_info, amount)| {
+                Ok(Asset {
+                    info: asset_info.to_normal(deps.api)?,
+                    amount: *amount,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?,
+        cw4_enabled: config.cw4_enabled,
+        tokens_per_weight: config.tokens_per_weight,
+        paused: config.paused,
+        receipt_token: config
+            .receipt_token
+            .map(|receipt_token| {
+                deps.api
+                    .addr_humanize(&receipt_token)
+                    .map(|addr| addr.to_string())
+            })
+            .transpose()?,
+    })
+}
+
+pub fn query_state(deps: Deps) -> StdResult<State> {
+    STATE.load(deps.storage)
+}
+
+pub fn query_user_info(deps: Deps, env: Env, user: String) -> StdResult<UserInfoResponse> {
+    let config = CONFIGURATION.load(deps.storage)?;
+    let user_raw = deps.api.addr_canonicalize(&user)?;
+
+    let mut state = STATE.load(deps.storage)?;
+    let mut schedules = REWARD_SCHEDULES.may_load(deps.storage)?.unwrap_or_default();
+    let released = release_rewards(
+        deps.storage,
+        &mut state,
+        &mut schedules,
+        env.block.time.seconds(),
+    )?;
+    let mut user_info = match released.user_info(&user_raw) {
+        Some(user_info) => user_info.clone(),
+        None => USER_INFO
+            .load(deps.storage, user_raw.as_slice())
+            .unwrap_or_default(),
+    };
+    settle_rewards(&state, &mut user_info)?;
+
+    Ok(UserInfoResponse {
+        stake_amount: user_info.stake_amount,
+        pending_rewards: config
+            .reward_asset_infos
+            .iter()
+            .zip(user_info.rewards.iter())
+            .map(|(asset_info, user_reward)| {
+                Ok(Asset {
+                    info: asset_info.to_normal(deps.api)?,
+                    amount: user_reward.pending_amount,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?,
+        auto_compound: user_info.auto_compound,
+    })
+}
+
+/// Users with a non-zero stake, in the order of their canonical addresses
+pub fn query_all_users(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllUsersResponse> {
+    let config = CONFIGURATION.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|user| deps.api.addr_canonicalize(&user))
+        .transpose()?
+        .map(|user_raw| Bound::exclusive(user_raw.as_slice()));
+
+    let mut state = STATE.load(deps.storage)?;
+    let mut schedules = REWARD_SCHEDULES.may_load(deps.storage)?.unwrap_or_default();
+    let released = release_rewards(
+        deps.storage,
+        &mut state,
+        &mut schedules,
+        env.block.time.seconds(),
+    )?;
+
+    let users = USER_INFO
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| {
+            item.as_ref()
+                .map_or(true, |(_, user_info)| !user_info.stake_amount.is_zero())
+        })
+        .take(limit)
+        .map(|item| {
+            let (user_raw, user_info) = item?;
+            let user_raw = CanonicalAddr::from(user_raw);
+            let mut user_info = released.user_info(&user_raw).cloned().unwrap_or(user_info);
+            settle_rewards(&state, &mut user_info)?;
+
+            Ok(UserResponse {
+                user: deps.api.addr_humanize(&user_raw)?.to_string(),
+                stake_amount: user_info.stake_amount,
+                pending_rewards: config
+                    .reward_asset_infos
+                    .iter()
+                    .zip(user_info.rewards.iter())
+                    .map(|(asset_info, user_reward)| {
+                        Ok(Asset {
+                            info: asset_info.to_normal(deps.api)?,
+                            amount: user_reward.pending_amount,
+                        })
+                    })
+                    .collect::<StdResult<Vec<_>>>()?,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AllUsersResponse { users })
+}
+
+pub fn query_user_stake_at_height(
+    deps: Deps,
+    user: String,
+    height: u64,
+) -> StdResult<StakeAtHeightResponse> {
+    let stake_amount = USER_STAKES
+        .may_load_at_height(
+            deps.storage,
+            deps.api.addr_canonicalize(&user)?.as_slice(),
+            height,
+        )?
+        .unwrap_or_default();
+
+    Ok(StakeAtHeightResponse {
+        stake_amount,
+        height,
+    })
+}
+
+pub fn query_total_stake_at_height(deps: Deps, height: u64) -> StdResult<StakeAtHeightResponse> {
+    let stake_amount = TOTAL_STAKE
+        .may_load_at_height(deps.storage, TOTAL_STAKE_KEY, height)?
+        .unwrap_or_default();
+
+    Ok(StakeAtHeightResponse {
+        stake_amount,
+        height,
+    })
+}
+
+/// Rewards which a claim of `user` would deliver now.
+/// Staking token rewards are left out if the user compounds them automatically.
+pub fn query_claimable_rewards(
+    deps: Deps,
+    env: Env,
+    user: String,
+) -> StdResult<ClaimableRewardsResponse> {
+    let config = CONFIGURATION.load(deps.storage)?;
+    let user_raw = deps.api.addr_canonicalize(&user)?;
+
+    let mut state = STATE.load(deps.storage)?;
+    let mut schedules = REWARD_SCHEDULES.may_load(deps.storage)?.unwrap_or_default();
+    let released = release_rewards(
+        deps.storage,
+        &mut state,
+        &mut schedules,
+        env.block.time.seconds(),
+    )?;
+    let mut user_info = match released.user_info(&user_raw) {
+        Some(user_info) => user_info.clone(),
+        None => USER_INFO
+            .load(deps.storage, user_raw.as_slice())
+            .unwrap_or_default(),
+    };
+    settle_rewards(&state, &mut user_info)?;
+    if user_info.auto_compound {
+        if let Some(reward_index) = get_staking_reward_index(&config) {
+            compound_rewards(&mut state, &mut user_info, reward_index)?;
+        }
+    }
+
+    let mut rewards: Vec<ClaimableReward> = vec![];
+    for ((asset_info, reward), user_reward) in config
+        .reward_asset_infos
+        .iter()
+        .zip(state.rewards.iter_mut())
+        .zip(user_info.rewards.iter())
+    {
+        if user_reward.pending_amount.is_zero() {
+            continue;
+        }
+
+        let asset = Asset {
+            info: asset_info.to_normal(deps.api)?,
+            amount: user_reward.pending_amount,
+        };
+        let (asset, tax_amount) = pay_reward_tax(&deps.querier, reward, asset)?;
+        rewards.push(ClaimableReward {
+            asset_info: asset.info,
+            amount: asset.amount,
+            tax_amount,
+        });
+    }
+
+    Ok(ClaimableRewardsResponse { rewards })
+}
+
+pub fn query_locks(deps: Deps, user: String) -> StdResult<LocksResponse> {
+    let user_info = USER_INFO
+        .load(deps.storage, deps.api.addr_canonicalize(&user)?.as_slice())
+        .unwrap_or_default();
+
+    Ok(LocksResponse {
+        locks: user_info.locks,
+    })
+}
+
+pub fn query_unbonds(deps: Deps, user: String) -> StdResult<UnbondsResponse> {
+    Ok(UnbondsResponse {
+        unbonds: UNBONDS
+            .may_load(deps.storage, deps.api.addr_canonicalize(&user)?.as_slice())?
+            .unwrap_or_default(),
+    })
+}
+
+pub fn query_reward_schedules(deps: Deps) -> StdResult<RewardSchedulesResponse> {
+    Ok(RewardSchedulesResponse {
+        schedules: REWARD_SCHEDULES.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
+pub fn query_solvency(deps: Deps, env: Env) -> StdResult<SolvencyResponse> {
+    let config = CONFIGURATION.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+    let schedules = REWARD_SCHEDULES.may_load(deps.storage)?.unwrap_or_default();
+
+    let mut rewards: Vec<RewardSolvency> = vec![];
+    for (reward_index, (asset_info, reward)) in config
+        .reward_asset_infos
+        .iter()
+        .zip(state.rewards.iter())
+        .enumerate()
+    {
+        let mut owed_amount = reward
+            .distributed_amount
+            .checked_sub(reward.claimed_amount)?
+            .checked_add(reward.undistributed_amount)?
+            .checked_add(reward.forfeited_amount)?
+            .checked_add(reward.tax_reserve)?;
+        for schedule in schedules
+            .iter()
+            .filter(|schedule| schedule.reward_index as usize == reward_index)
+        {
+            owed_amount =
+                owed_amount.checked_add(schedule.amount.checked_sub(schedule.released_amount)?)?;
+        }
+        if asset_info.equal(&config.staking_asset_info) {
+            owed_amount = owed_amount
+                .checked_add(state.total_deposits)?
+                .checked_add(state.total_unbonding)?;
+        }
+
+        let asset_info = asset_info.to_normal(deps.api)?;
+        rewards.push(RewardSolvency {
+            balance: asset_info.query_pool(
+                &deps.querier,
+                deps.api,
+                env.contract.address.clone(),
+            )?,
+            asset_info,
+            owed_amount,
+            tax_reserve: reward.tax_reserve,
+        });
+    }
+
+    Ok(SolvencyResponse { rewards })
+}
+
+pub fn query_funders(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<FundersResponse> {
+    let config = CONFIGURATION.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|funder| deps.api.addr_canonicalize(&funder))
+        .transpose()?
+        .map(|funder_raw| Bound::exclusive(funder_raw.as_slice()));
+
+    let funders = FUNDERS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (funder_raw, funder_info) = item?;
+
+            let mut caps: Vec<FundingCap> = vec![];
+            let mut funded_amounts: Vec<Asset> = vec![];
+            for (reward_index, asset_info) in config.reward_asset_infos.iter().enumerate() {
+                let funder_reward = funder_info
+                    .rewards
+                    .get(reward_index)
+                    .cloned()
+                    .unwrap_or_default();
+                if let Some(cap_amount) = funder_reward.cap_amount {
+                    caps.push(FundingCap {
+                        asset_info: asset_info.to_normal(deps.api)?,
+                        period: funder_reward.cap_period,
+                        amount: cap_amount,
+                    });
+                }
+                funded_amounts.push(Asset {
+                    info: asset_info.to_normal(deps.api)?,
+                    amount: funder_reward.funded_amount,
+                });
+            }
+
+            Ok(FunderResponse {
+                funder: deps
+                    .api
+                    .addr_humanize(&CanonicalAddr::from(funder_raw))?
+                    .to_string(),
+                enabled: funder_info.enabled,
+                caps,
+                funded_amounts,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(FundersResponse { funders })
+}
+
+pub fn query_fund_history(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<FundHistoryResponse> {
+    let config = CONFIGURATION.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|id| Bound::exclusive(U64Key::from(id)));
+
+    let records = FUND_HISTORY
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, record) = item?;
+
+            Ok(FundRecord {
+                id: record.id,
+                funder: deps.api.addr_humanize(&record.funder)?.to_string(),
+                asset: Asset {
+                    info: config.reward_asset_infos[record.reward_index as usize]
+                        .to_normal(deps.api)?,
+                    amount: record.amount,
+                },
+                time: record.time,
+                start_time: record.start_time,
+                end_time: record.end_time,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(FundHistoryResponse { records })
+}
+
+pub fn query_approvals(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ApprovalsResponse> {
+    let owner_raw = deps.api.addr_canonicalize(&owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|operator| deps.api.addr_canonicalize(&operator))
+        .transpose()?
+        .map(|operator_raw| Bound::exclusive(operator_raw.as_slice()));
+
+    let approvals = APPROVALS
+        .prefix(owner_raw.as_slice())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (operator_raw, approval) = item?;
+
+            Ok(Approval {
+                operator: deps
+                    .api
+                    .addr_humanize(&CanonicalAddr::from(operator_raw))?
+                    .to_string(),
+                can_withdraw: approval.can_withdraw,
+                expires: approval.expires,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ApprovalsResponse { approvals })
+}
+
+pub fn query_swap_pairs(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<SwapPairsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|pair| deps.api.addr_canonicalize(&pair))
+        .transpose()?
+        .map(|pair_raw| Bound::exclusive(pair_raw.as_slice()));
+
+    let pairs = SWAP_PAIRS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (pair_raw, asset_infos) = item?;
+
+            Ok(SwapPair {
+                pair: deps
+                    .api
+                    .addr_humanize(&CanonicalAddr::from(pair_raw))?
+                    .to_string(),
+                asset_infos: [
+                    asset_infos[0].to_normal(deps.api)?,
+                    asset_infos[1].to_normal(deps.api)?,
+                ],
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(SwapPairsResponse { pairs })
+}
+
+pub fn query_hooks(deps: Deps) -> StdResult<HooksResponse> {
+    let hooks = HOOKS
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .iter()
+        .map(|hook| Ok(deps.api.addr_humanize(hook)?.to_string()))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(HooksResponse { hooks })
+}
+
+pub fn query_member(deps: Deps, addr: String, at_height: Option<u64>) -> StdResult<MemberResponse> {
+    let tokens_per_weight = load_tokens_per_weight(deps)?;
+    let addr_raw = deps.api.addr_canonicalize(&addr)?;
+    let stake_amount = match at_height {
+        Some(height) => {
+            USER_STAKES.may_load_at_height(deps.storage, addr_raw.as_slice(), height)?
+        }
+        None => USER_INFO
+            .may_load(deps.storage, addr_raw.as_slice())?
+            .map(|user_info| user_info.stake_amount),
+    }
+    .unwrap_or_default();
+
+    let weight = to_weight(stake_amount, tokens_per_weight)?;
+
+    Ok(MemberResponse {
+        weight: if weight == 0 { None } else { Some(weight) },
+    })
+}
+
+pub fn query_total_weight(deps: Deps, at_height: Option<u64>) -> StdResult<TotalWeightResponse> {
+    let tokens_per_weight = load_tokens_per_weight(deps)?;
+    let total_deposits = match at_height {
+        Some(height) => TOTAL_STAKE
+            .may_load_at_height(deps.storage, TOTAL_STAKE_KEY, height)?
+            .unwrap_or_default(),
+        None => STATE.load(deps.storage)?.total_deposits,
+    };
+
+    Ok(TotalWeightResponse {
+        weight: to_weight(total_deposits, tokens_per_weight)?,
+    })
+}
+
+pub fn query_list_members(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<MemberListResponse> {
+    let tokens_per_weight = load_tokens_per_weight(deps)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|addr| deps.api.addr_canonicalize(&addr))
+        .transpose()?
+        .map(|addr_raw| Bound::exclusive(addr_raw.as_slice()));
+
+    let members = USER_INFO
+        .range(deps.storage, start, None, Order::Ascending)
+        .map(|item| {
+            let (addr_raw, user_info) = item?;
+
+            Ok(Member {
+                addr: deps
+                    .api
+                    .addr_humanize(&CanonicalAddr::from(addr_raw))?
+                    .to_string(),
+                weight: to_weight(user_info.stake_amount, tokens_per_weight)?,
+            })
+        })
+        .filter(|member| member.as_ref().map_or(true, |member| member.weight != 0))
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(MemberListResponse { members })
+}
+
+/// Divisor of the cw4 weights, if the cw4 queries are enabled
+fn load_tokens_per_weight(deps: Deps) -> StdResult<Uint128> {
+    let config = CONFIGURATION.load(deps.storage)?;
+    if !config.cw4_enabled {
+        return Err(StdError::generic_err("cw4 queries are disabled"));
+    }
+
+    Ok(config.tokens_per_weight)
+}
+
+/// cw4 weights are `u64`, weights which do not fit are rejected instead of truncated
+fn to_weight(amount: Uint128, tokens_per_weight: Uint128) -> StdResult<u64> {
+    u64::try_from((amount / tokens_per_weight).u128())
+        .map_err(|_| StdError::generic_err("weight overflows u64"))
+}
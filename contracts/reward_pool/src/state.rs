@@ -1,27 +1,149 @@
-use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
-
-use cosmwasm_std::{CanonicalAddr, Uint128};
-use cw_storage_plus::{Item, Map};
-
-use reward_pool::reward_pool::State;
-use terraswap::asset::AssetInfoRaw;
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct Config {
-    pub governance: CanonicalAddr,
-    pub funder: CanonicalAddr,
-    pub staking_token: CanonicalAddr,
-    pub reward_asset_info: AssetInfoRaw,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
-pub struct UserInfo {
-    pub stake_amount: Uint128,
-    pub pending_amount: Uint128,
-    pub reward_debt: Uint128,
-}
-
-pub const CONFIGURATION: Item<Config> = Item::new("config");
-pub const STATE: Item<State> = Item::new("state");
-pub const USER_INFO: Map<&[u8], UserInfo> = Map::new("user_infos");
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Api, CanonicalAddr, StdResult, Uint128};
+use cw20::Expiration;
+use cw_storage_plus::{Item, Map, SnapshotMap, Strategy, U64Key};
+
+use reward_pool::reward_pool::{
+    ForfeitMode, LockPosition, LockTier, PauseFlags, PendingHandover, RewardSchedule, State,
+    Unbond, UserReward,
+};
+use terraswap::asset::AssetInfoRaw;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub governance: CanonicalAddr,
+    pub pending_governance: Option<PendingHandoverRaw>,
+    pub guardian: Option<CanonicalAddr>,
+    pub staking_asset_info: AssetInfoRaw,
+    pub reward_asset_infos: Vec<AssetInfoRaw>,
+    pub lock_tiers: Vec<LockTier>,
+    pub unbonding_period: u64,
+    pub forfeit_mode: ForfeitMode,
+    pub permissionless_funding: bool,
+    /// Indexed in the same order as `reward_asset_infos` and may be shorter,
+    /// reward assets without an entry have no minimum
+    pub min_fund_amounts: Vec<Uint128>,
+    pub cw4_enabled: bool,
+    /// Divisor of the stakes in the cw4 weights, never zero
+    pub tokens_per_weight: Uint128,
+    pub paused: PauseFlags,
+    /// Set in the reply of the receipt token instantiation
+    pub receipt_token: Option<CanonicalAddr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingHandoverRaw {
+    pub address: CanonicalAddr,
+    pub expires_at: u64,
+}
+
+impl PendingHandoverRaw {
+    pub fn to_normal(&self, api: &dyn Api) -> StdResult<PendingHandover> {
+        Ok(PendingHandover {
+            address: api.addr_humanize(&self.address)?.to_string(),
+            expires_at: self.expires_at,
+        })
+    }
+}
+
+/// `rewards` is indexed in the same order as `Config.reward_asset_infos`
+/// and may be shorter if reward assets were added after the last update.
+/// `stake_amount` includes the amounts of `locks`. Expired locks are dropped
+/// at their end time through `LOCK_EXPIRIES` or by the next action of the user.
+/// If `auto_compound` is set, the pending staking token rewards
+/// are added to the stake whenever the rewards of the user are settled.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct UserInfo {
+    pub stake_amount: Uint128,
+    pub weight: Uint128,
+    pub locks: Vec<LockPosition>,
+    pub rewards: Vec<UserReward>,
+    pub auto_compound: bool,
+}
+
+/// `rewards` is indexed in the same order as `Config.reward_asset_infos`
+/// and may be shorter if reward assets were added after the last update.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct FunderInfo {
+    pub enabled: bool,
+    pub rewards: Vec<FunderReward>,
+}
+
+/// `period_funded_amount` is the amount funded since `period_start`,
+/// which is restarted once `cap_period` has passed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct FunderReward {
+    pub cap_amount: Option<Uint128>,
+    pub cap_period: u64,
+    pub period_start: u64,
+    pub period_funded_amount: Uint128,
+    pub funded_amount: Uint128,
+}
+
+/// `reward_index` is the index of the funded asset in `Config.reward_asset_infos`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FundRecordRaw {
+    pub id: u64,
+    pub funder: CanonicalAddr,
+    pub reward_index: u32,
+    pub amount: Uint128,
+    pub time: u64,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorApproval {
+    pub can_withdraw: bool,
+    pub expires: Expiration,
+}
+
+/// Users with lock positions ending at `end_time`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockExpiry {
+    pub end_time: u64,
+    pub users: Vec<CanonicalAddr>,
+}
+
+/// Claim swap which is completed in the reply of the last swap. `balance_before` is
+/// the balance of the ask asset before the swaps, without the claimed ask asset rewards
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingSwap {
+    pub recipient: CanonicalAddr,
+    pub ask_asset_info: AssetInfoRaw,
+    pub balance_before: Uint128,
+    pub minimum_receive: Uint128,
+    pub remaining_swaps: u32,
+}
+
+pub const CONFIGURATION: Item<Config> = Item::new("config");
+pub const STATE: Item<State> = Item::new("state");
+pub const REWARD_SCHEDULES: Item<Vec<RewardSchedule>> = Item::new("reward_schedules");
+pub const USER_INFO: Map<&[u8], UserInfo> = Map::new("user_infos");
+pub const USER_STAKES: SnapshotMap<&[u8], Uint128> = SnapshotMap::new(
+    "user_stakes",
+    "user_stakes__checkpoints",
+    "user_stakes__changelog",
+    Strategy::EveryBlock,
+);
+// cw-storage-plus 0.8 has no `SnapshotItem`, so the total stake is kept under a single key
+pub const TOTAL_STAKE: SnapshotMap<&str, Uint128> = SnapshotMap::new(
+    "total_stake",
+    "total_stake__checkpoints",
+    "total_stake__changelog",
+    Strategy::EveryBlock,
+);
+pub const TOTAL_STAKE_KEY: &str = "total";
+/// Keyed by end time, processed in order while the rewards are released
+pub const LOCK_EXPIRIES: Map<U64Key, LockExpiry> = Map::new("lock_expiries");
+pub const UNBONDS: Map<&[u8], Vec<Unbond>> = Map::new("unbonds");
+pub const FUNDERS: Map<&[u8], FunderInfo> = Map::new("funders");
+pub const FUND_HISTORY: Map<U64Key, FundRecordRaw> = Map::new("fund_history");
+pub const FUND_HISTORY_COUNT: Item<u64> = Item::new("fund_history_count");
+/// Keyed by (owner, operator)
+pub const APPROVALS: Map<(&[u8], &[u8]), OperatorApproval> = Map::new("approvals");
+pub const SWAP_PAIRS: Map<&[u8], [AssetInfoRaw; 2]> = Map::new("swap_pairs");
+pub const HOOKS: Item<Vec<CanonicalAddr>> = Item::new("hooks");
+pub const PENDING_SWAP: Item<PendingSwap> = Item::new("pending_swap");
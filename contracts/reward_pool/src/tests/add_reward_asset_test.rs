@@ -0,0 +1,151 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{attr, from_binary, Decimal256, Uint128};
+
+use reward_pool::reward_pool::{
+    ConfigResponse, ExecuteMsg, ForfeitMode, PauseFlags, QueryMsg, RewardState, State,
+};
+use terraswap::asset::AssetInfo;
+
+use crate::{
+    contract::{execute, query},
+    error::ContractError,
+    tests::{
+        mock_querier::mock_dependencies,
+        test_utils::{instantiate_reward_pool, lock_tiers},
+    },
+};
+
+#[test]
+fn fails_if_caller_is_not_governance() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    let msg = ExecuteMsg::AddRewardAsset {
+        asset_info: AssetInfo::Token {
+            contract_addr: String::from("reward_token"),
+        },
+    };
+
+    let info = mock_info("policy", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+}
+
+#[test]
+fn fails_if_duplicate_reward_asset() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let msg = ExecuteMsg::AddRewardAsset {
+        asset_info: reward_asset_info,
+    };
+
+    let info = mock_info("governance", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::DuplicateRewardAsset {
+            asset_info: "uusd".to_string()
+        }
+    );
+}
+
+#[test]
+fn add_reward_asset() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let new_reward_asset_info = AssetInfo::Token {
+        contract_addr: String::from("reward_token"),
+    };
+
+    let msg = ExecuteMsg::AddRewardAsset {
+        asset_info: new_reward_asset_info.clone(),
+    };
+
+    let info = mock_info("governance", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "add_reward_asset"),
+            attr("asset_info", "reward_token"),
+        ]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+
+    assert_eq!(
+        ConfigResponse {
+            governance: String::from("governance"),
+            pending_governance: None,
+            guardian: None,
+            staking_asset_info: AssetInfo::Token {
+                contract_addr: String::from("staking_token"),
+            },
+            reward_asset_infos: vec![reward_asset_info, new_reward_asset_info],
+            lock_tiers: lock_tiers(),
+            unbonding_period: 0,
+            forfeit_mode: ForfeitMode::Redistribute,
+            permissionless_funding: false,
+            min_fund_amounts: vec![],
+            cw4_enabled: false,
+            tokens_per_weight: Uint128::from(1u128),
+            paused: PauseFlags::default(),
+            receipt_token: None,
+        },
+        config_res
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(
+        State {
+            total_deposits: Uint128::zero(),
+            total_weight: Uint128::zero(),
+            total_unbonding: Uint128::zero(),
+            staker_count: 0,
+            rewards: vec![
+                RewardState {
+                    acc_per_share: Decimal256::zero(),
+                    undistributed_amount: Uint128::zero(),
+                    distributed_amount: Uint128::zero(),
+                    claimed_amount: Uint128::zero(),
+                    forfeited_amount: Uint128::zero(),
+                    tax_reserve: Uint128::zero(),
+                },
+                RewardState {
+                    acc_per_share: Decimal256::zero(),
+                    undistributed_amount: Uint128::zero(),
+                    distributed_amount: Uint128::zero(),
+                    claimed_amount: Uint128::zero(),
+                    forfeited_amount: Uint128::zero(),
+                    tax_reserve: Uint128::zero(),
+                },
+            ],
+        },
+        state_res
+    );
+}
@@ -0,0 +1,167 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{coin, from_binary, to_binary, OwnedDeps, Uint128};
+
+use cw20::Cw20ReceiveMsg;
+use reward_pool::reward_pool::{
+    AllUsersResponse, Cw20HookMsg, ExecuteMsg, QueryMsg, State, UserResponse,
+};
+use terraswap::asset::{Asset, AssetInfo};
+
+use crate::{
+    contract::{execute, query},
+    tests::{
+        mock_querier::{mock_dependencies, WasmMockQuerier},
+        test_utils::instantiate_reward_pool,
+    },
+};
+
+fn deposit_for(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    user: &str,
+    amount: u128,
+) {
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: user.to_string(),
+        msg: to_binary(&Cw20HookMsg::Deposit {
+            recipient: None,
+            lock_duration: None,
+        })
+        .unwrap(),
+        amount: Uint128::from(amount),
+    });
+
+    let info = mock_info("staking_token", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+fn withdraw_all(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, user: &str) {
+    let msg = ExecuteMsg::EmergencyWithdraw {};
+
+    let info = mock_info(user, &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+fn query_staker_count(deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> u64 {
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+    state_res.staker_count
+}
+
+fn query_all_users(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Vec<UserResponse> {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AllUsers { start_after, limit },
+    )
+    .unwrap();
+
+    let all_users_res: AllUsersResponse = from_binary(&res).unwrap();
+    all_users_res.users
+}
+
+#[test]
+fn count_stakers() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    deposit_for(&mut deps, "addr", 100);
+    deposit_for(&mut deps, "addr", 100);
+    deposit_for(&mut deps, "staker", 100);
+    assert_eq!(query_staker_count(&deps), 2);
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: Uint128::from(200u128),
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(query_staker_count(&deps), 1);
+
+    withdraw_all(&mut deps, "staker");
+    assert_eq!(query_staker_count(&deps), 0);
+
+    deposit_for(&mut deps, "addr", 100);
+    assert_eq!(query_staker_count(&deps), 1);
+}
+
+#[test]
+fn list_stakers_with_pending_rewards() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    deposit_for(&mut deps, "addr", 100);
+    deposit_for(&mut deps, "staker", 300);
+    deposit_for(&mut deps, "leaver", 100);
+    withdraw_all(&mut deps, "leaver");
+
+    let info = mock_info("funder", &[coin(400, "uusd")]);
+
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+
+    let mut users = query_all_users(&deps, None, None);
+    users.sort_by(|a, b| a.user.cmp(&b.user));
+    assert_eq!(
+        users,
+        vec![
+            UserResponse {
+                user: "addr".to_string(),
+                stake_amount: Uint128::from(100u128),
+                pending_rewards: vec![Asset {
+                    info: reward_asset_info.clone(),
+                    amount: Uint128::from(100u128),
+                }],
+            },
+            UserResponse {
+                user: "staker".to_string(),
+                stake_amount: Uint128::from(300u128),
+                pending_rewards: vec![Asset {
+                    info: reward_asset_info,
+                    amount: Uint128::from(300u128),
+                }],
+            },
+        ]
+    );
+}
+
+#[test]
+fn paginate_stakers() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    for user in ["addr", "staker", "whale"] {
+        deposit_for(&mut deps, user, 100);
+    }
+
+    let all_users = query_all_users(&deps, None, None);
+    assert_eq!(all_users.len(), 3);
+
+    let first_page = query_all_users(&deps, None, Some(2));
+    assert_eq!(first_page, all_users[..2].to_vec());
+
+    let second_page = query_all_users(&deps, Some(first_page[1].user.clone()), Some(2));
+    assert_eq!(second_page, all_users[2..].to_vec());
+}
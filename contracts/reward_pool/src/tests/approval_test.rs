@@ -0,0 +1,236 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, BankMsg, Coin, CosmosMsg, OwnedDeps, SubMsg, Uint128, WasmMsg,
+};
+
+use cw20::{Cw20ExecuteMsg, Expiration};
+use reward_pool::reward_pool::{Approval, ApprovalsResponse, ExecuteMsg, QueryMsg};
+
+use crate::{
+    contract::{execute, query},
+    error::ContractError,
+    tests::{
+        mock_querier::{mock_dependencies, WasmMockQuerier},
+        test_utils::{deposit, instantiate_reward_pool, mock_env_at, uusd},
+    },
+};
+
+fn approve(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    operator: &str,
+    can_withdraw: bool,
+    expires: Option<Expiration>,
+) -> Result<(), ContractError> {
+    let msg = ExecuteMsg::Approve {
+        operator: operator.to_string(),
+        can_withdraw,
+        expires,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg)?;
+
+    Ok(())
+}
+
+fn query_approvals(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Vec<Approval> {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Approvals {
+            owner: "addr".to_string(),
+            start_after,
+            limit,
+        },
+    )
+    .unwrap();
+
+    let approvals_res: ApprovalsResponse = from_binary(&res).unwrap();
+    approvals_res.approvals
+}
+
+#[test]
+fn approve_and_revoke() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    let expires = Expiration::AtTime(mock_env().block.time.plus_seconds(100));
+    let msg = ExecuteMsg::Approve {
+        operator: "keeper".to_string(),
+        can_withdraw: false,
+        expires: Some(expires),
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "approve"),
+            attr("owner", "addr"),
+            attr("operator", "keeper"),
+            attr("can_withdraw", "false"),
+            attr("expires", expires.to_string()),
+        ]
+    );
+
+    approve(&mut deps, "compounder", true, None).unwrap();
+
+    // approvals are ordered by the canonical address of the operator
+    assert_eq!(
+        query_approvals(&deps, None, None),
+        vec![
+            Approval {
+                operator: String::from("keeper"),
+                can_withdraw: false,
+                expires,
+            },
+            Approval {
+                operator: String::from("compounder"),
+                can_withdraw: true,
+                expires: Expiration::Never {},
+            },
+        ]
+    );
+    assert_eq!(
+        query_approvals(&deps, Some(String::from("keeper")), Some(1)),
+        vec![Approval {
+            operator: String::from("compounder"),
+            can_withdraw: true,
+            expires: Expiration::Never {},
+        }]
+    );
+
+    let msg = ExecuteMsg::Revoke {
+        operator: "keeper".to_string(),
+    };
+
+    let info = mock_info("addr", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        query_approvals(&deps, None, None),
+        vec![Approval {
+            operator: String::from("compounder"),
+            can_withdraw: true,
+            expires: Expiration::Never {},
+        }]
+    );
+}
+
+#[test]
+fn fails_to_approve_invalid() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    let res = approve(&mut deps, "addr", false, None).unwrap_err();
+    assert_eq!(res, ContractError::CannotApproveSelf {});
+
+    let expires = Expiration::AtHeight(mock_env().block.height);
+    let res = approve(&mut deps, "keeper", false, Some(expires)).unwrap_err();
+    assert_eq!(res, ContractError::ApprovalExpired {});
+}
+
+#[test]
+fn claim_for_user() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(50u128),
+        }],
+    );
+
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+
+    let msg = ExecuteMsg::ClaimFor {
+        user: "addr".to_string(),
+    };
+
+    let info = mock_info("keeper", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let now = mock_env().block.time.seconds();
+    approve(
+        &mut deps,
+        "keeper",
+        false,
+        Some(Expiration::AtTime(mock_env().block.time.plus_seconds(100))),
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at(now + 101),
+        info.clone(),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::ApprovalExpired {});
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("keeper"),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(50u128),
+            }]
+        })),]
+    );
+}
+
+#[test]
+fn withdraw_for_user() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let msg = ExecuteMsg::WithdrawFor {
+        user: "addr".to_string(),
+        amount: Uint128::from(40u128),
+    };
+
+    let info = mock_info("keeper", &[]);
+
+    approve(&mut deps, "keeper", false, None).unwrap();
+
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    approve(&mut deps, "keeper", true, None).unwrap();
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("staking_token"),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: String::from("keeper"),
+                amount: Uint128::from(40u128),
+            })
+            .unwrap(),
+        })),]
+    );
+}
@@ -0,0 +1,342 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Api, Coin, ContractResult, CosmosMsg, Decimal, OverflowError,
+    OverflowOperation, OwnedDeps, Reply, SubMsg, SubMsgExecutionResponse, Uint128, WasmMsg,
+};
+
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use reward_pool::reward_pool::{
+    ClaimSwap, Cw20HookMsg, ExecuteMsg, QueryMsg, SwapPair, SwapPairsResponse,
+};
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::pair::ExecuteMsg as PairExecuteMsg;
+
+use crate::{
+    contract::{execute, query, reply},
+    error::ContractError,
+    execute::SWAP_REPLY_ID,
+    state::{PendingSwap, PENDING_SWAP},
+    tests::{
+        mock_querier::{mock_dependencies, WasmMockQuerier},
+        test_utils::{deposit, instantiate_reward_pool, uusd},
+    },
+};
+
+fn ask_token() -> AssetInfo {
+    AssetInfo::Token {
+        contract_addr: "ask_token".to_string(),
+    }
+}
+
+fn add_swap_pair(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    sender: &str,
+) -> Result<(), ContractError> {
+    let msg = ExecuteMsg::AddSwapPair {
+        pair: "pair".to_string(),
+        asset_infos: [uusd(), ask_token()],
+    };
+
+    let info = mock_info(sender, &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg)?;
+
+    Ok(())
+}
+
+fn fund_and_claim_with_swap(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    minimum_receive: u128,
+) -> Result<Vec<SubMsg>, ContractError> {
+    deposit(deps, Uint128::from(100u128)).unwrap();
+
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100u128),
+        }],
+    );
+
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+
+    let msg = ExecuteMsg::Claim {
+        recipient: None,
+        swap: Some(ClaimSwap {
+            ask_asset_info: ask_token(),
+            max_slippage: Decimal::percent(10),
+            minimum_receive: Uint128::from(minimum_receive),
+        }),
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg)?;
+
+    Ok(res.messages)
+}
+
+fn swap_reply(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    received: u128,
+) -> Result<Vec<SubMsg>, ContractError> {
+    deps.querier.with_token_balances(&[(
+        &String::from("ask_token"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::from(received))],
+    )]);
+
+    let msg = Reply {
+        id: SWAP_REPLY_ID,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+
+    let res = reply(deps.as_mut(), mock_env(), msg)?;
+
+    Ok(res.messages)
+}
+
+#[test]
+fn add_and_remove_swap_pair() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    let res = add_swap_pair(&mut deps, "addr").unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    add_swap_pair(&mut deps, "governance").unwrap();
+
+    let query_swap_pairs = |deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>| {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SwapPairs {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let swap_pairs_res: SwapPairsResponse = from_binary(&res).unwrap();
+        swap_pairs_res.pairs
+    };
+
+    assert_eq!(
+        query_swap_pairs(&deps),
+        vec![SwapPair {
+            pair: String::from("pair"),
+            asset_infos: [uusd(), ask_token()],
+        }]
+    );
+
+    let msg = ExecuteMsg::RemoveSwapPair {
+        pair: "pair".to_string(),
+    };
+
+    let info = mock_info("governance", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(query_swap_pairs(&deps), vec![]);
+}
+
+#[test]
+fn fails_if_pair_is_not_whitelisted() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    let res = fund_and_claim_with_swap(&mut deps, 0).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::NoSwapPair {
+            offer: uusd().to_string(),
+            ask: ask_token().to_string(),
+        }
+    );
+}
+
+#[test]
+fn claim_with_swap() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+    add_swap_pair(&mut deps, "governance").unwrap();
+
+    let messages = fund_and_claim_with_swap(&mut deps, 180).unwrap();
+    assert_eq!(
+        messages,
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("pair"),
+                funds: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: Uint128::from(100u128),
+                }],
+                msg: to_binary(&PairExecuteMsg::Swap {
+                    offer_asset: Asset {
+                        info: uusd(),
+                        amount: Uint128::from(100u128),
+                    },
+                    belief_price: None,
+                    max_spread: Some(Decimal::percent(10)),
+                    to: None,
+                })
+                .unwrap(),
+            }),
+            SWAP_REPLY_ID,
+        )]
+    );
+
+    let messages = swap_reply(&mut deps, 190).unwrap();
+    assert_eq!(
+        messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("ask_token"),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: String::from("addr"),
+                amount: Uint128::from(190u128),
+            })
+            .unwrap(),
+        }))]
+    );
+}
+
+#[test]
+fn fails_if_slippage_exceeded() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+    add_swap_pair(&mut deps, "governance").unwrap();
+
+    fund_and_claim_with_swap(&mut deps, 180).unwrap();
+
+    let res = swap_reply(&mut deps, 179).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::SwapSlippageExceeded {
+            received: Uint128::from(179u128),
+            minimum_receive: Uint128::from(180u128),
+        }
+    );
+}
+
+#[test]
+fn return_correct_logs_on_reply() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+    add_swap_pair(&mut deps, "governance").unwrap();
+
+    fund_and_claim_with_swap(&mut deps, 100).unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &String::from("ask_token"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::from(100u128))],
+    )]);
+
+    let msg = Reply {
+        id: SWAP_REPLY_ID,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+
+    let res = reply(deps.as_mut(), mock_env(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "claim_swap"),
+            attr("recipient", "addr"),
+            attr("amount", "100ask_token"),
+        ]
+    );
+}
+
+#[test]
+fn forward_ask_asset_rewards_with_swapped_amount() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+    add_swap_pair(&mut deps, "governance").unwrap();
+
+    let msg = ExecuteMsg::AddRewardAsset {
+        asset_info: ask_token(),
+    };
+
+    let info = mock_info("governance", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "funder".to_string(),
+        msg: to_binary(&Cw20HookMsg::Fund {}).unwrap(),
+        amount: Uint128::from(50u128),
+    });
+
+    let info = mock_info("ask_token", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &String::from("ask_token"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::from(50u128))],
+    )]);
+
+    let messages = fund_and_claim_with_swap(&mut deps, 240).unwrap();
+    assert_eq!(messages.len(), 1);
+
+    let messages = swap_reply(&mut deps, 240).unwrap();
+    assert_eq!(
+        messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("ask_token"),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: String::from("addr"),
+                amount: Uint128::from(240u128),
+            })
+            .unwrap(),
+        }))]
+    );
+}
+
+#[test]
+fn fails_if_no_pending_swap() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    let res = swap_reply(&mut deps, 100).unwrap_err();
+    assert_eq!(res, ContractError::NoPendingSwap {});
+}
+
+#[test]
+fn fails_if_no_remaining_swaps() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    PENDING_SWAP
+        .save(
+            &mut deps.storage,
+            &PendingSwap {
+                recipient: deps.api.addr_canonicalize("addr").unwrap(),
+                ask_asset_info: ask_token().to_raw(&deps.api).unwrap(),
+                balance_before: Uint128::zero(),
+                minimum_receive: Uint128::zero(),
+                remaining_swaps: 0,
+            },
+        )
+        .unwrap();
+
+    let res = swap_reply(&mut deps, 100).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::Overflow(OverflowError::new(OverflowOperation::Sub, 0, 1))
+    );
+}
@@ -1,177 +1,383 @@
-use cosmwasm_std::testing::{mock_env, mock_info};
-use cosmwasm_std::{
-    attr, from_binary, BankMsg, Coin, CosmosMsg, Decimal, StdError, SubMsg, Uint128,
-};
-
-use reward_pool::reward_pool::{ExecuteMsg, QueryMsg, State, UserInfoResponse};
-use terraswap::asset::AssetInfo;
-
-use crate::{
-    contract::{execute, query},
-    state::STATE,
-    tests::{
-        mock_querier::mock_dependencies,
-        test_utils::{deposit, instantiate_reward_pool},
-    },
-};
-
-#[test]
-fn fails_if_nothing_to_claim() {
-    let mut deps = mock_dependencies(&[]);
-
-    let reward_asset_info = AssetInfo::NativeToken {
-        denom: "uusd".to_string(),
-    };
-
-    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
-
-    let msg = ExecuteMsg::Claim {};
-
-    let info = mock_info("addr", &[]);
-
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
-    assert_eq!(res, StdError::generic_err("reward is zero"));
-}
-
-#[test]
-fn update_user_info() {
-    let mut deps = mock_dependencies(&[]);
-
-    let reward_asset_info = AssetInfo::NativeToken {
-        denom: "uusd".to_string(),
-    };
-
-    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
-
-    let stake_amount = Uint128::from(100u128);
-
-    deposit(&mut deps, stake_amount).unwrap();
-
-    let total_deposits = Uint128::from(100u128);
-    let acc_per_share = Decimal::percent(50);
-
-    STATE
-        .save(
-            &mut deps.storage,
-            &State {
-                acc_per_share,
-                total_deposits,
-            },
-        )
-        .unwrap();
-
-    let msg = ExecuteMsg::Claim {};
-
-    let info = mock_info("addr", &[]);
-
-    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-    let res = query(
-        deps.as_ref(),
-        mock_env(),
-        QueryMsg::UserInfo {
-            user: "addr".to_string(),
-        },
-    )
-    .unwrap();
-
-    let user_info_res: UserInfoResponse = from_binary(&res).unwrap();
-
-    assert_eq!(
-        UserInfoResponse {
-            stake_amount: stake_amount,
-            pending_amount: Uint128::zero(),
-        },
-        user_info_res
-    );
-}
-
-#[test]
-fn transfer_reward_token() {
-    let mut deps = mock_dependencies(&[]);
-
-    let reward_asset_info = AssetInfo::NativeToken {
-        denom: "uusd".to_string(),
-    };
-
-    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
-
-    let stake_amount = Uint128::from(100u128);
-
-    deposit(&mut deps, stake_amount).unwrap();
-
-    let total_deposits = Uint128::from(100u128);
-    let acc_per_share = Decimal::percent(50);
-
-    STATE
-        .save(
-            &mut deps.storage,
-            &State {
-                acc_per_share,
-                total_deposits,
-            },
-        )
-        .unwrap();
-
-    let msg = ExecuteMsg::Claim {};
-
-    let info = mock_info("addr", &[]);
-
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-    assert_eq!(
-        res.messages,
-        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
-            to_address: String::from("addr"),
-            amount: vec![Coin {
-                denom: "uusd".to_string(),
-                amount: acc_per_share * stake_amount
-            }]
-        })),]
-    );
-}
-
-#[test]
-fn return_correct_logs() {
-    let mut deps = mock_dependencies(&[]);
-
-    let reward_asset_info = AssetInfo::NativeToken {
-        denom: "uusd".to_string(),
-    };
-
-    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
-
-    let tax_rate = Decimal::from_ratio(1u128, 1000u128);
-    deps.querier.with_tax(tax_rate, &[]);
-
-    let stake_amount = Uint128::from(100u128);
-
-    deposit(&mut deps, stake_amount).unwrap();
-
-    let total_deposits = Uint128::from(100u128);
-    let acc_per_share = Decimal::percent(50);
-
-    STATE
-        .save(
-            &mut deps.storage,
-            &State {
-                acc_per_share,
-                total_deposits,
-            },
-        )
-        .unwrap();
-
-    let msg = ExecuteMsg::Claim {};
-
-    let info = mock_info("addr", &[]);
-
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-    let reward_without_tax = acc_per_share * stake_amount;
-    let reward_with_tax = reward_without_tax - reward_without_tax * tax_rate;
-
-    assert_eq!(
-        res.attributes,
-        vec![attr("action", "claim"), attr("amount", reward_with_tax),]
-    );
-}
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, BankMsg, Coin, CosmosMsg, Decimal, Decimal256, SubMsg, Uint128,
+    WasmMsg,
+};
+
+use cw20::Cw20ExecuteMsg;
+
+use reward_pool::reward_pool::{ExecuteMsg, QueryMsg, RewardState, State, UserInfoResponse};
+use terraswap::asset::{Asset, AssetInfo};
+
+use crate::{
+    contract::{execute, query},
+    error::ContractError,
+    state::STATE,
+    tests::{
+        mock_querier::mock_dependencies,
+        test_utils::{deposit, instantiate_reward_pool},
+    },
+    utils::compute_reward,
+};
+
+#[test]
+fn fails_if_nothing_to_claim() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let msg = ExecuteMsg::Claim {
+        recipient: None,
+        swap: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::ZeroReward {});
+}
+
+#[test]
+fn update_user_info() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let stake_amount = Uint128::from(100u128);
+
+    deposit(&mut deps, stake_amount).unwrap();
+
+    let total_deposits = Uint128::from(100u128);
+    let acc_per_share = Decimal256::percent(50);
+
+    STATE
+        .save(
+            &mut deps.storage,
+            &State {
+                total_deposits,
+                total_weight: total_deposits,
+                total_unbonding: Uint128::zero(),
+                staker_count: 0,
+                rewards: vec![RewardState {
+                    acc_per_share,
+                    undistributed_amount: Uint128::zero(),
+                    distributed_amount: Uint128::zero(),
+                    claimed_amount: Uint128::zero(),
+                    forfeited_amount: Uint128::zero(),
+                    tax_reserve: Uint128::zero(),
+                }],
+            },
+        )
+        .unwrap();
+
+    let msg = ExecuteMsg::Claim {
+        recipient: None,
+        swap: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UserInfo {
+            user: "addr".to_string(),
+        },
+    )
+    .unwrap();
+
+    let user_info_res: UserInfoResponse = from_binary(&res).unwrap();
+
+    assert_eq!(
+        UserInfoResponse {
+            stake_amount,
+            pending_rewards: vec![Asset {
+                info: reward_asset_info.clone(),
+                amount: Uint128::zero(),
+            }],
+            auto_compound: false,
+        },
+        user_info_res
+    );
+}
+
+#[test]
+fn transfer_reward_token() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let stake_amount = Uint128::from(100u128);
+
+    deposit(&mut deps, stake_amount).unwrap();
+
+    let total_deposits = Uint128::from(100u128);
+    let acc_per_share = Decimal256::percent(50);
+
+    STATE
+        .save(
+            &mut deps.storage,
+            &State {
+                total_deposits,
+                total_weight: total_deposits,
+                total_unbonding: Uint128::zero(),
+                staker_count: 0,
+                rewards: vec![RewardState {
+                    acc_per_share,
+                    undistributed_amount: Uint128::zero(),
+                    distributed_amount: Uint128::zero(),
+                    claimed_amount: Uint128::zero(),
+                    forfeited_amount: Uint128::zero(),
+                    tax_reserve: Uint128::zero(),
+                }],
+            },
+        )
+        .unwrap();
+
+    let msg = ExecuteMsg::Claim {
+        recipient: None,
+        swap: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("addr"),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: compute_reward(stake_amount, acc_per_share).unwrap()
+            }]
+        })),]
+    );
+}
+
+#[test]
+fn return_correct_logs() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let tax_rate = Decimal::from_ratio(1u128, 1000u128);
+    deps.querier.with_tax(tax_rate, &[]);
+
+    let stake_amount = Uint128::from(100u128);
+
+    deposit(&mut deps, stake_amount).unwrap();
+
+    let total_deposits = Uint128::from(100u128);
+    let acc_per_share = Decimal256::percent(50);
+
+    STATE
+        .save(
+            &mut deps.storage,
+            &State {
+                total_deposits,
+                total_weight: total_deposits,
+                total_unbonding: Uint128::zero(),
+                staker_count: 0,
+                rewards: vec![RewardState {
+                    acc_per_share,
+                    undistributed_amount: Uint128::zero(),
+                    distributed_amount: Uint128::zero(),
+                    claimed_amount: Uint128::zero(),
+                    forfeited_amount: Uint128::zero(),
+                    tax_reserve: Uint128::zero(),
+                }],
+            },
+        )
+        .unwrap();
+
+    let msg = ExecuteMsg::Claim {
+        recipient: None,
+        swap: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the pool pays the tax, so the full reward is claimed
+    let reward = compute_reward(stake_amount, acc_per_share).unwrap();
+
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "claim"),
+            attr("recipient", "addr"),
+            attr("amount", format!("{}uusd", reward)),
+        ]
+    );
+}
+
+#[test]
+fn transfer_multiple_reward_assets() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let msg = ExecuteMsg::AddRewardAsset {
+        asset_info: AssetInfo::Token {
+            contract_addr: String::from("reward_token"),
+        },
+    };
+
+    let info = mock_info("governance", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let stake_amount = Uint128::from(100u128);
+
+    deposit(&mut deps, stake_amount).unwrap();
+
+    let total_deposits = Uint128::from(100u128);
+    let acc_per_share0 = Decimal256::percent(50);
+    let acc_per_share1 = Decimal256::percent(200);
+
+    STATE
+        .save(
+            &mut deps.storage,
+            &State {
+                total_deposits,
+                total_weight: total_deposits,
+                total_unbonding: Uint128::zero(),
+                staker_count: 0,
+                rewards: vec![
+                    RewardState {
+                        acc_per_share: acc_per_share0,
+                        undistributed_amount: Uint128::zero(),
+                        distributed_amount: Uint128::zero(),
+                        claimed_amount: Uint128::zero(),
+                        forfeited_amount: Uint128::zero(),
+                        tax_reserve: Uint128::zero(),
+                    },
+                    RewardState {
+                        acc_per_share: acc_per_share1,
+                        undistributed_amount: Uint128::zero(),
+                        distributed_amount: Uint128::zero(),
+                        claimed_amount: Uint128::zero(),
+                        forfeited_amount: Uint128::zero(),
+                        tax_reserve: Uint128::zero(),
+                    },
+                ],
+            },
+        )
+        .unwrap();
+
+    let msg = ExecuteMsg::Claim {
+        recipient: None,
+        swap: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("addr"),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: compute_reward(stake_amount, acc_per_share0).unwrap()
+                }]
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("reward_token"),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("addr"),
+                    amount: compute_reward(stake_amount, acc_per_share1).unwrap()
+                })
+                .unwrap(),
+            })),
+        ]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "claim"),
+            attr("recipient", "addr"),
+            attr("amount", "50uusd,200reward_token")
+        ]
+    );
+}
+
+#[test]
+fn claim_to_recipient() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let stake_amount = Uint128::from(100u128);
+
+    deposit(&mut deps, stake_amount).unwrap();
+
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(50u128),
+        }],
+    );
+
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+
+    let msg = ExecuteMsg::Claim {
+        recipient: Some("wallet".to_string()),
+        swap: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "claim"),
+            attr("recipient", "wallet"),
+            attr("amount", "50uusd"),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("wallet"),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(50u128),
+            }]
+        })),]
+    );
+}
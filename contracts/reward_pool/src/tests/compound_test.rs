@@ -0,0 +1,153 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{attr, from_binary, OwnedDeps, Uint128};
+
+use reward_pool::reward_pool::{ExecuteMsg, QueryMsg, State, UserInfoResponse};
+use terraswap::asset::{Asset, AssetInfo};
+
+use crate::{
+    contract::{execute, query},
+    error::ContractError,
+    tests::{
+        mock_querier::{mock_dependencies, WasmMockQuerier},
+        test_utils::{deposit, fund, instantiate_reward_pool},
+    },
+};
+
+fn staking_token() -> AssetInfo {
+    AssetInfo::Token {
+        contract_addr: "staking_token".to_string(),
+    }
+}
+
+fn query_user_info(deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> UserInfoResponse {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UserInfo {
+            user: "addr".to_string(),
+        },
+    )
+    .unwrap();
+
+    from_binary(&res).unwrap()
+}
+
+#[test]
+fn fails_if_staking_token_is_not_reward_asset() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Compound {}).unwrap_err();
+    assert_eq!(res, ContractError::StakingTokenNotRewarded {});
+}
+
+#[test]
+fn compound_pending_rewards() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, staking_token()).unwrap();
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::Compound {},
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::ZeroReward {});
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+    fund(&mut deps, "funder", staking_token(), 50).unwrap();
+
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Compound {}).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "compound"), attr("amount", "50")]
+    );
+    assert!(res.messages.is_empty());
+
+    assert_eq!(
+        query_user_info(&deps),
+        UserInfoResponse {
+            stake_amount: Uint128::from(150u128),
+            pending_rewards: vec![Asset {
+                info: staking_token(),
+                amount: Uint128::zero(),
+            }],
+            auto_compound: false,
+        }
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+    let state_res: State = from_binary(&res).unwrap();
+    assert_eq!(state_res.total_deposits, Uint128::from(150u128));
+    assert_eq!(state_res.total_weight, Uint128::from(150u128));
+    assert_eq!(state_res.rewards[0].claimed_amount, Uint128::from(50u128));
+}
+
+#[test]
+fn auto_compound_on_settlement() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, staking_token()).unwrap();
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::SetAutoCompound { enabled: true },
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::NoStake {});
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::SetAutoCompound { enabled: true },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "set_auto_compound"), attr("enabled", "true")]
+    );
+
+    fund(&mut deps, "funder", staking_token(), 50).unwrap();
+    deposit(&mut deps, Uint128::from(10u128)).unwrap();
+
+    let user_info_res = query_user_info(&deps);
+    assert_eq!(user_info_res.stake_amount, Uint128::from(160u128));
+    assert!(user_info_res.auto_compound);
+
+    // claim succeeds without transfer if the rewards were compounded
+    fund(&mut deps, "funder", staking_token(), 40).unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::Claim {
+            recipient: None,
+            swap: None,
+        },
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+
+    assert_eq!(query_user_info(&deps).stake_amount, Uint128::from(200u128));
+}
@@ -0,0 +1,239 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{from_binary, OwnedDeps, StdError, Uint128};
+
+use reward_pool::reward_pool::{
+    ExecuteMsg, Member, MemberListResponse, MemberResponse, QueryMsg, TotalWeightResponse,
+};
+use terraswap::asset::AssetInfo;
+
+use crate::{
+    contract::{execute, query},
+    error::ContractError,
+    tests::{
+        mock_querier::{mock_dependencies, WasmMockQuerier},
+        test_utils::{deposit_at, instantiate_reward_pool, mock_env_at_height, uusd},
+    },
+};
+
+fn enable_cw4(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    tokens_per_weight: u128,
+) -> Result<(), ContractError> {
+    let msg = ExecuteMsg::UpdateConfig {
+        guardian: None,
+        unbonding_period: None,
+        forfeit_mode: None,
+        permissionless_funding: None,
+        min_fund_amounts: None,
+        cw4_enabled: Some(true),
+        tokens_per_weight: Some(Uint128::from(tokens_per_weight)),
+    };
+
+    let info = mock_info("governance", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg)?;
+
+    Ok(())
+}
+
+fn query_member(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    addr: &str,
+    at_height: Option<u64>,
+) -> Option<u64> {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Member {
+            addr: addr.to_string(),
+            at_height,
+        },
+    )
+    .unwrap();
+
+    let member_res: MemberResponse = from_binary(&res).unwrap();
+    member_res.weight
+}
+
+fn query_total_weight(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    at_height: Option<u64>,
+) -> u64 {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::TotalWeight { at_height },
+    )
+    .unwrap();
+
+    let total_weight_res: TotalWeightResponse = from_binary(&res).unwrap();
+    total_weight_res.weight
+}
+
+fn query_list_members(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Vec<Member> {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ListMembers { start_after, limit },
+    )
+    .unwrap();
+
+    let member_list_res: MemberListResponse = from_binary(&res).unwrap();
+    member_list_res.members
+}
+
+#[test]
+fn map_stake_to_weight() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+    enable_cw4(&mut deps, 1).unwrap();
+
+    assert_eq!(query_member(&deps, "addr", None), None);
+    assert_eq!(query_total_weight(&deps, None), 0);
+
+    deposit_at(&mut deps, "addr", 100, 100);
+    deposit_at(&mut deps, "staker", 300, 200);
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: Uint128::from(30u128),
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    execute(deps.as_mut(), mock_env_at_height(300), info, msg).unwrap();
+
+    assert_eq!(query_member(&deps, "addr", None), Some(70));
+    assert_eq!(query_member(&deps, "staker", None), Some(300));
+    assert_eq!(query_total_weight(&deps, None), 370);
+
+    assert_eq!(query_member(&deps, "addr", Some(100)), None);
+    assert_eq!(query_member(&deps, "addr", Some(101)), Some(100));
+    assert_eq!(query_member(&deps, "staker", Some(200)), None);
+    assert_eq!(query_total_weight(&deps, Some(201)), 400);
+    assert_eq!(query_total_weight(&deps, Some(301)), 370);
+}
+
+#[test]
+fn list_members() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+    enable_cw4(&mut deps, 1).unwrap();
+
+    for user in ["addr", "staker", "whale", "leaver"] {
+        deposit_at(&mut deps, user, 100, 100);
+    }
+
+    let info = mock_info("leaver", &[]);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::EmergencyWithdraw {},
+    )
+    .unwrap();
+
+    let mut members = query_list_members(&deps, None, None);
+    assert_eq!(members.len(), 3);
+
+    let first_page = query_list_members(&deps, None, Some(2));
+    assert_eq!(first_page, members[..2].to_vec());
+
+    let second_page = query_list_members(&deps, Some(first_page[1].addr.clone()), Some(2));
+    assert_eq!(second_page, members[2..].to_vec());
+
+    members.sort_by(|a, b| a.addr.cmp(&b.addr));
+    assert_eq!(
+        members,
+        ["addr", "staker", "whale"]
+            .iter()
+            .map(|addr| Member {
+                addr: addr.to_string(),
+                weight: 100,
+            })
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn fails_if_cw4_is_disabled() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    deposit_at(&mut deps, "addr", 100, 100);
+
+    for msg in [
+        QueryMsg::Member {
+            addr: "addr".to_string(),
+            at_height: None,
+        },
+        QueryMsg::TotalWeight { at_height: None },
+        QueryMsg::ListMembers {
+            start_after: None,
+            limit: None,
+        },
+    ] {
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap_err();
+        assert_eq!(res, StdError::generic_err("cw4 queries are disabled"));
+    }
+}
+
+#[test]
+fn fails_if_tokens_per_weight_is_zero() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    let res = enable_cw4(&mut deps, 0).unwrap_err();
+    assert_eq!(res, ContractError::InvalidTokensPerWeight {});
+}
+
+#[test]
+fn divide_stake_by_tokens_per_weight() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+    enable_cw4(&mut deps, 1_000_000).unwrap();
+
+    // the stake exceeds u64, but its weight does not
+    let stake_amount = (u64::MAX - 1) as u128 * 1_000_000;
+    deposit_at(&mut deps, "whale", stake_amount, 100);
+    deposit_at(&mut deps, "minnow", 999_999, 100);
+
+    assert_eq!(query_member(&deps, "whale", None), Some(u64::MAX - 1));
+    assert_eq!(query_member(&deps, "minnow", None), None);
+    assert_eq!(query_total_weight(&deps, None), u64::MAX - 1);
+    assert_eq!(
+        query_list_members(&deps, None, None),
+        vec![Member {
+            addr: "whale".to_string(),
+            weight: u64::MAX - 1,
+        }]
+    );
+
+    deposit_at(&mut deps, "minnow", 1_000_001, 200);
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::TotalWeight { at_height: None },
+    )
+    .unwrap_err();
+    assert_eq!(res, StdError::generic_err("weight overflows u64"));
+}
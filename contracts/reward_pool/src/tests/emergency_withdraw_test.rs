@@ -0,0 +1,419 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, BankMsg, Coin, CosmosMsg, OwnedDeps, SubMsg, Uint128, WasmMsg,
+};
+
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use reward_pool::reward_pool::{Cw20HookMsg, ExecuteMsg, ForfeitMode, QueryMsg, State, Unbond};
+use terraswap::asset::AssetInfo;
+
+use crate::{
+    contract::{execute, query},
+    error::ContractError,
+    tests::{
+        mock_querier::{mock_dependencies, WasmMockQuerier},
+        test_utils::{
+            deposit, fund, instantiate_reward_pool, mock_env_at, query_pending_reward,
+            query_unbonds, uusd, MONTH,
+        },
+    },
+};
+
+fn deposit_other(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, amount: Uint128) {
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "other".to_string(),
+        msg: to_binary(&Cw20HookMsg::Deposit {
+            recipient: None,
+            lock_duration: None,
+        })
+        .unwrap(),
+        amount,
+    });
+
+    let info = mock_info("staking_token", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+fn set_forfeit_mode(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    forfeit_mode: ForfeitMode,
+) {
+    let msg = ExecuteMsg::UpdateConfig {
+        guardian: None,
+        unbonding_period: None,
+        forfeit_mode: Some(forfeit_mode),
+        permissionless_funding: None,
+        min_fund_amounts: None,
+        cw4_enabled: None,
+        tokens_per_weight: None,
+    };
+
+    let info = mock_info("governance", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+#[test]
+fn fails_if_user_has_no_stake() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    let msg = ExecuteMsg::EmergencyWithdraw {};
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::ZeroAmount {});
+}
+
+#[test]
+fn return_full_stake() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    let stake_amount = Uint128::from(100u128);
+
+    deposit(&mut deps, stake_amount).unwrap();
+    fund(&mut deps, "funder", uusd(), 100).unwrap();
+
+    let msg = ExecuteMsg::EmergencyWithdraw {};
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("staking_token"),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: String::from("addr"),
+                amount: stake_amount,
+            })
+            .unwrap(),
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "emergency_withdraw"),
+            attr("amount", stake_amount),
+            attr("forfeited_amount", "100uusd"),
+        ]
+    );
+
+    assert_eq!(
+        query_pending_reward(&deps, mock_env(), "addr"),
+        Uint128::zero()
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(state_res.total_deposits, Uint128::zero());
+    assert_eq!(state_res.total_weight, Uint128::zero());
+    assert_eq!(state_res.rewards[0].distributed_amount, Uint128::zero());
+    assert_eq!(
+        state_res.rewards[0].undistributed_amount,
+        Uint128::from(100u128)
+    );
+}
+
+#[test]
+fn redistribute_forfeited_rewards() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+    deposit_other(&mut deps, Uint128::from(100u128));
+    fund(&mut deps, "funder", uusd(), 100).unwrap();
+
+    let msg = ExecuteMsg::EmergencyWithdraw {};
+
+    let info = mock_info("addr", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        query_pending_reward(&deps, mock_env(), "other"),
+        Uint128::from(100u128)
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(
+        state_res.rewards[0].distributed_amount,
+        Uint128::from(100u128)
+    );
+    assert_eq!(state_res.rewards[0].forfeited_amount, Uint128::zero());
+}
+
+#[test]
+fn set_aside_forfeited_rewards() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+    set_forfeit_mode(&mut deps, ForfeitMode::SetAside);
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+    deposit_other(&mut deps, Uint128::from(100u128));
+    fund(&mut deps, "funder", uusd(), 100).unwrap();
+
+    let msg = ExecuteMsg::EmergencyWithdraw {};
+
+    let info = mock_info("addr", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        query_pending_reward(&deps, mock_env(), "other"),
+        Uint128::from(50u128)
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(
+        state_res.rewards[0].distributed_amount,
+        Uint128::from(50u128)
+    );
+    assert_eq!(state_res.rewards[0].forfeited_amount, Uint128::from(50u128));
+}
+
+#[test]
+fn sweep_forfeited_rewards() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+    set_forfeit_mode(&mut deps, ForfeitMode::SetAside);
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+    fund(&mut deps, "funder", uusd(), 100).unwrap();
+
+    let msg = ExecuteMsg::EmergencyWithdraw {};
+
+    let info = mock_info("addr", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::SweepForfeitedRewards {
+        recipient: "treasury".to_string(),
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let info = mock_info("governance", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("treasury"),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(100u128),
+            }],
+        }))]
+    );
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::ZeroReward {});
+}
+
+#[test]
+fn unbond_locked_stake() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        msg: to_binary(&Cw20HookMsg::Deposit {
+            recipient: None,
+            lock_duration: Some(MONTH),
+        })
+        .unwrap(),
+        amount: Uint128::from(50u128),
+    });
+
+    let info = mock_info("staking_token", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    fund(&mut deps, "funder", uusd(), 100).unwrap();
+
+    let info = mock_info("addr", &[]);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::Claim {
+            recipient: None,
+            swap: None,
+        },
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::EmergencyWithdraw {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("staking_token"),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: String::from("addr"),
+                amount: Uint128::from(100u128),
+            })
+            .unwrap(),
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "emergency_withdraw"),
+            attr("amount", "150"),
+            attr("forfeited_amount", ""),
+            attr("unbonding_amount", "50"),
+        ]
+    );
+
+    let end_time = mock_env().block.time.seconds() + MONTH;
+    assert_eq!(
+        query_unbonds(&deps, "addr"),
+        vec![Unbond {
+            amount: Uint128::from(50u128),
+            release_time: end_time,
+            recipient: "addr".to_string(),
+        }]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(state_res.total_deposits, Uint128::zero());
+    assert_eq!(state_res.total_weight, Uint128::zero());
+    assert_eq!(state_res.total_unbonding, Uint128::from(50u128));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at(end_time - 1),
+        info.clone(),
+        ExecuteMsg::ClaimUnbonded {},
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::NoReleasedUnbonds {});
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at(end_time),
+        info,
+        ExecuteMsg::ClaimUnbonded {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("staking_token"),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: String::from("addr"),
+                amount: Uint128::from(50u128),
+            })
+            .unwrap(),
+        }))]
+    );
+}
+
+#[test]
+fn unbond_stake_if_unbonding_period_is_set() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        guardian: None,
+        unbonding_period: Some(100),
+        forfeit_mode: None,
+        permissionless_funding: None,
+        min_fund_amounts: None,
+        cw4_enabled: None,
+        tokens_per_weight: None,
+    };
+
+    let info = mock_info("governance", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::EmergencyWithdraw {},
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![]);
+
+    assert_eq!(
+        query_unbonds(&deps, "addr"),
+        vec![Unbond {
+            amount: Uint128::from(100u128),
+            release_time: mock_env().block.time.seconds() + 100,
+            recipient: "addr".to_string(),
+        }]
+    );
+}
@@ -1,271 +1,507 @@
-use cosmwasm_std::testing::{mock_env, mock_info};
-use cosmwasm_std::{attr, from_binary, to_binary, Coin, Decimal, StdError, Uint128};
-
-use cw20::Cw20ReceiveMsg;
-use reward_pool::reward_pool::{Cw20HookMsg, ExecuteMsg, QueryMsg, State};
-use terraswap::asset::AssetInfo;
-
-use crate::{
-    contract::{execute, query},
-    state::STATE,
-    tests::{mock_querier::mock_dependencies, test_utils::instantiate_reward_pool},
-};
-
-#[test]
-fn fails_if_caller_is_not_funder() {
-    let mut deps = mock_dependencies(&[]);
-
-    let reward_asset_info = AssetInfo::NativeToken {
-        denom: "uusd".to_string(),
-    };
-
-    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
-
-    let msg = ExecuteMsg::Fund {};
-
-    let info = mock_info(
-        "policy",
-        &[Coin {
-            denom: "uusd".to_string(),
-            amount: Uint128::from(1u128),
-        }],
-    );
-
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
-    assert_eq!(res, StdError::generic_err("unauthorized"));
-}
-
-#[test]
-fn fails_if_amount_is_zero() {
-    let mut deps = mock_dependencies(&[]);
-
-    let reward_asset_info = AssetInfo::NativeToken {
-        denom: "uusd".to_string(),
-    };
-
-    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
-
-    let msg = ExecuteMsg::Fund {};
-
-    let info = mock_info(
-        "funder",
-        &[Coin {
-            denom: "uusd".to_string(),
-            amount: Uint128::zero(),
-        }],
-    );
-
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
-    assert_eq!(res, StdError::generic_err("amount is zero"));
-}
-
-#[test]
-fn fails_if_no_deposits() {
-    let mut deps = mock_dependencies(&[]);
-
-    let reward_asset_info = AssetInfo::NativeToken {
-        denom: "uusd".to_string(),
-    };
-
-    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
-
-    let msg = ExecuteMsg::Fund {};
-
-    let info = mock_info(
-        "funder",
-        &[Coin {
-            denom: "uusd".to_string(),
-            amount: Uint128::from(1u128),
-        }],
-    );
-
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
-    assert_eq!(res, StdError::generic_err("no deposits"));
-}
-
-#[test]
-fn fails_if_multiple_denom_received() {
-    let mut deps = mock_dependencies(&[]);
-
-    let reward_asset_info = AssetInfo::NativeToken {
-        denom: "uusd".to_string(),
-    };
-
-    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
-
-    let msg = ExecuteMsg::Fund {};
-
-    let info = mock_info(
-        "funder",
-        &[
-            Coin {
-                denom: "ueur".to_string(),
-                amount: Uint128::from(1u128),
-            },
-            Coin {
-                denom: "uusd".to_string(),
-                amount: Uint128::from(1u128),
-            },
-        ],
-    );
-
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
-    assert_eq!(res, StdError::generic_err("invalid denom received"));
-}
-
-#[test]
-fn fails_if_cw20_received() {
-    let mut deps = mock_dependencies(&[]);
-
-    let reward_asset_info = AssetInfo::NativeToken {
-        denom: "uusd".to_string(),
-    };
-
-    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
-
-    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-        sender: "funder".to_string(),
-        msg: to_binary(&Cw20HookMsg::Fund {}).unwrap(),
-        amount: Uint128::from(1u128),
-    });
-
-    let info = mock_info("reward_token", &[]);
-
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
-    assert_eq!(res, StdError::generic_err("invalid reward token"));
-}
-
-#[test]
-fn fund_rewards() {
-    let mut deps = mock_dependencies(&[]);
-
-    let reward_asset_info = AssetInfo::NativeToken {
-        denom: "uusd".to_string(),
-    };
-
-    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
-
-    let total_deposits = Uint128::from(100u128);
-    let reward_amount = Uint128::from(1000000000u128);
-
-    STATE
-        .save(
-            &mut deps.storage,
-            &State {
-                acc_per_share: Decimal::zero(),
-                total_deposits,
-            },
-        )
-        .unwrap();
-    let msg = ExecuteMsg::Fund {};
-
-    let info = mock_info(
-        "funder",
-        &[Coin {
-            denom: "uusd".to_string(),
-            amount: reward_amount,
-        }],
-    );
-
-    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
-
-    let state_res: State = from_binary(&res).unwrap();
-
-    assert_eq!(
-        State {
-            acc_per_share: Decimal::from_ratio(reward_amount, total_deposits),
-            total_deposits,
-        },
-        state_res
-    );
-}
-
-#[test]
-fn increase_acc_per_share() {
-    let mut deps = mock_dependencies(&[]);
-
-    let reward_asset_info = AssetInfo::NativeToken {
-        denom: "uusd".to_string(),
-    };
-
-    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
-
-    let total_deposits = Uint128::from(100u128);
-    let reward_amount = Uint128::from(1000000000u128);
-    let acc_per_share = Decimal::percent(50);
-
-    STATE
-        .save(
-            &mut deps.storage,
-            &State {
-                acc_per_share,
-                total_deposits,
-            },
-        )
-        .unwrap();
-    let msg = ExecuteMsg::Fund {};
-
-    let info = mock_info(
-        "funder",
-        &[Coin {
-            denom: "uusd".to_string(),
-            amount: reward_amount,
-        }],
-    );
-
-    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
-
-    let state_res: State = from_binary(&res).unwrap();
-
-    assert_eq!(
-        State {
-            acc_per_share: acc_per_share + Decimal::from_ratio(reward_amount, total_deposits),
-            total_deposits,
-        },
-        state_res
-    );
-}
-
-#[test]
-fn return_correct_logs() {
-    let mut deps = mock_dependencies(&[]);
-
-    let reward_asset_info = AssetInfo::NativeToken {
-        denom: "uusd".to_string(),
-    };
-
-    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
-
-    let total_deposits = Uint128::from(100u128);
-    let reward_amount = Uint128::from(1000000000u128);
-
-    STATE
-        .save(
-            &mut deps.storage,
-            &State {
-                acc_per_share: Decimal::zero(),
-                total_deposits,
-            },
-        )
-        .unwrap();
-    let msg = ExecuteMsg::Fund {};
-
-    let info = mock_info(
-        "funder",
-        &[Coin {
-            denom: "uusd".to_string(),
-            amount: reward_amount,
-        }],
-    );
-
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-    assert_eq!(
-        res.attributes,
-        vec![attr("action", "fund"), attr("amount", reward_amount),]
-    );
-}
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{attr, from_binary, to_binary, Coin, Decimal256, Uint128};
+
+use cw20::Cw20ReceiveMsg;
+use reward_pool::reward_pool::{
+    Cw20HookMsg, ExecuteMsg, QueryMsg, RewardState, State, UserInfoResponse,
+};
+use terraswap::asset::{Asset, AssetInfo};
+
+use crate::{
+    contract::{execute, query},
+    error::ContractError,
+    state::STATE,
+    tests::{
+        mock_querier::mock_dependencies,
+        test_utils::{deposit, instantiate_reward_pool},
+    },
+};
+
+#[test]
+fn fails_if_caller_is_not_funder() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    let msg = ExecuteMsg::Fund {};
+
+    let info = mock_info(
+        "policy",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1u128),
+        }],
+    );
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+}
+
+#[test]
+fn fails_if_amount_is_zero() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let msg = ExecuteMsg::Fund {};
+
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::zero(),
+        }],
+    );
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::ZeroAmount {});
+}
+
+#[test]
+fn keep_undistributed_rewards_if_no_deposits() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let reward_amount = Uint128::from(1000u128);
+
+    let msg = ExecuteMsg::Fund {};
+
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: reward_amount,
+        }],
+    );
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(
+        State {
+            total_deposits: Uint128::zero(),
+            total_weight: Uint128::zero(),
+            total_unbonding: Uint128::zero(),
+            staker_count: 0,
+            rewards: vec![RewardState {
+                acc_per_share: Decimal256::zero(),
+                undistributed_amount: reward_amount,
+                distributed_amount: Uint128::zero(),
+                claimed_amount: Uint128::zero(),
+                forfeited_amount: Uint128::zero(),
+                tax_reserve: Uint128::zero(),
+            }],
+        },
+        state_res
+    );
+}
+
+#[test]
+fn distribute_undistributed_rewards_on_deposit() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let reward_amount = Uint128::from(1000u128);
+
+    let msg = ExecuteMsg::Fund {};
+
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: reward_amount,
+        }],
+    );
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let stake_amount = Uint128::from(100u128);
+
+    deposit(&mut deps, stake_amount).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(
+        State {
+            total_deposits: stake_amount,
+            total_weight: stake_amount,
+            total_unbonding: Uint128::zero(),
+            staker_count: 1,
+            rewards: vec![RewardState {
+                acc_per_share: Decimal256::from_ratio(reward_amount, stake_amount),
+                undistributed_amount: Uint128::zero(),
+                distributed_amount: reward_amount,
+                claimed_amount: Uint128::zero(),
+                forfeited_amount: Uint128::zero(),
+                tax_reserve: Uint128::zero(),
+            }],
+        },
+        state_res
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UserInfo {
+            user: "addr".to_string(),
+        },
+    )
+    .unwrap();
+
+    let user_info_res: UserInfoResponse = from_binary(&res).unwrap();
+
+    assert_eq!(
+        UserInfoResponse {
+            stake_amount,
+            pending_rewards: vec![Asset {
+                info: reward_asset_info,
+                amount: reward_amount,
+            }],
+            auto_compound: false,
+        },
+        user_info_res
+    );
+}
+
+#[test]
+fn distribute_undistributed_rewards_on_fund() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let total_deposits = Uint128::from(100u128);
+    let undistributed_amount = Uint128::from(500u128);
+    let reward_amount = Uint128::from(1000u128);
+
+    STATE
+        .save(
+            &mut deps.storage,
+            &State {
+                total_deposits,
+                total_weight: total_deposits,
+                total_unbonding: Uint128::zero(),
+                staker_count: 0,
+                rewards: vec![RewardState {
+                    acc_per_share: Decimal256::zero(),
+                    undistributed_amount,
+                    distributed_amount: Uint128::zero(),
+                    claimed_amount: Uint128::zero(),
+                    forfeited_amount: Uint128::zero(),
+                    tax_reserve: Uint128::zero(),
+                }],
+            },
+        )
+        .unwrap();
+
+    let msg = ExecuteMsg::Fund {};
+
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: reward_amount,
+        }],
+    );
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(
+        State {
+            total_deposits,
+            total_weight: total_deposits,
+            total_unbonding: Uint128::zero(),
+            staker_count: 0,
+            rewards: vec![RewardState {
+                acc_per_share: Decimal256::from_ratio(
+                    reward_amount + undistributed_amount,
+                    total_deposits
+                ),
+                undistributed_amount: Uint128::zero(),
+                distributed_amount: reward_amount + undistributed_amount,
+                claimed_amount: Uint128::zero(),
+                forfeited_amount: Uint128::zero(),
+                tax_reserve: Uint128::zero(),
+            }],
+        },
+        state_res
+    );
+}
+
+#[test]
+fn fails_if_multiple_denom_received() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let msg = ExecuteMsg::Fund {};
+
+    let info = mock_info(
+        "funder",
+        &[
+            Coin {
+                denom: "ueur".to_string(),
+                amount: Uint128::from(1u128),
+            },
+            Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1u128),
+            },
+        ],
+    );
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::InvalidDenomReceived { received: 2 });
+}
+
+#[test]
+fn fails_if_cw20_received() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "funder".to_string(),
+        msg: to_binary(&Cw20HookMsg::Fund {}).unwrap(),
+        amount: Uint128::from(1u128),
+    });
+
+    let info = mock_info("reward_token", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InvalidRewardToken {
+            token: "reward_token".to_string()
+        }
+    );
+}
+
+#[test]
+fn fund_rewards() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let total_deposits = Uint128::from(100u128);
+    let reward_amount = Uint128::from(1000000000u128);
+
+    STATE
+        .save(
+            &mut deps.storage,
+            &State {
+                total_deposits,
+                total_weight: total_deposits,
+                total_unbonding: Uint128::zero(),
+                staker_count: 0,
+                rewards: vec![RewardState {
+                    acc_per_share: Decimal256::zero(),
+                    undistributed_amount: Uint128::zero(),
+                    distributed_amount: Uint128::zero(),
+                    claimed_amount: Uint128::zero(),
+                    forfeited_amount: Uint128::zero(),
+                    tax_reserve: Uint128::zero(),
+                }],
+            },
+        )
+        .unwrap();
+    let msg = ExecuteMsg::Fund {};
+
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: reward_amount,
+        }],
+    );
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(
+        State {
+            total_deposits,
+            total_weight: total_deposits,
+            total_unbonding: Uint128::zero(),
+            staker_count: 0,
+            rewards: vec![RewardState {
+                acc_per_share: Decimal256::from_ratio(reward_amount, total_deposits),
+                undistributed_amount: Uint128::zero(),
+                distributed_amount: reward_amount,
+                claimed_amount: Uint128::zero(),
+                forfeited_amount: Uint128::zero(),
+                tax_reserve: Uint128::zero(),
+            }],
+        },
+        state_res
+    );
+}
+
+#[test]
+fn increase_acc_per_share() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let total_deposits = Uint128::from(100u128);
+    let reward_amount = Uint128::from(1000000000u128);
+    let acc_per_share = Decimal256::percent(50);
+
+    STATE
+        .save(
+            &mut deps.storage,
+            &State {
+                total_deposits,
+                total_weight: total_deposits,
+                total_unbonding: Uint128::zero(),
+                staker_count: 0,
+                rewards: vec![RewardState {
+                    acc_per_share,
+                    undistributed_amount: Uint128::zero(),
+                    distributed_amount: Uint128::zero(),
+                    claimed_amount: Uint128::zero(),
+                    forfeited_amount: Uint128::zero(),
+                    tax_reserve: Uint128::zero(),
+                }],
+            },
+        )
+        .unwrap();
+    let msg = ExecuteMsg::Fund {};
+
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: reward_amount,
+        }],
+    );
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(
+        State {
+            total_deposits,
+            total_weight: total_deposits,
+            total_unbonding: Uint128::zero(),
+            staker_count: 0,
+            rewards: vec![RewardState {
+                acc_per_share: acc_per_share
+                    + Decimal256::from_ratio(reward_amount, total_deposits),
+                undistributed_amount: Uint128::zero(),
+                distributed_amount: reward_amount,
+                claimed_amount: Uint128::zero(),
+                forfeited_amount: Uint128::zero(),
+                tax_reserve: Uint128::zero(),
+            }],
+        },
+        state_res
+    );
+}
+
+#[test]
+fn return_correct_logs() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let total_deposits = Uint128::from(100u128);
+    let reward_amount = Uint128::from(1000000000u128);
+
+    STATE
+        .save(
+            &mut deps.storage,
+            &State {
+                total_deposits,
+                total_weight: total_deposits,
+                total_unbonding: Uint128::zero(),
+                staker_count: 0,
+                rewards: vec![RewardState {
+                    acc_per_share: Decimal256::zero(),
+                    undistributed_amount: Uint128::zero(),
+                    distributed_amount: Uint128::zero(),
+                    claimed_amount: Uint128::zero(),
+                    forfeited_amount: Uint128::zero(),
+                    tax_reserve: Uint128::zero(),
+                }],
+            },
+        )
+        .unwrap();
+    let msg = ExecuteMsg::Fund {};
+
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: reward_amount,
+        }],
+    );
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "fund"),
+            attr("funder", "funder"),
+            attr("amount", reward_amount),
+            attr("tax_amount", "0"),
+        ]
+    );
+}
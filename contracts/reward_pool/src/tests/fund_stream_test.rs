@@ -0,0 +1,324 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{attr, from_binary, to_binary, BankMsg, Coin, CosmosMsg, SubMsg, Uint128};
+
+use cw20::Cw20ReceiveMsg;
+use reward_pool::reward_pool::{
+    Cw20HookMsg, ExecuteMsg, QueryMsg, RewardSchedule, RewardSchedulesResponse, UserInfoResponse,
+};
+use terraswap::asset::{Asset, AssetInfo};
+
+use crate::{
+    contract::{execute, query},
+    error::ContractError,
+    tests::{
+        mock_querier::mock_dependencies,
+        test_utils::{deposit, instantiate_reward_pool, mock_env_at},
+    },
+};
+
+#[test]
+fn fails_if_caller_is_not_funder() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let start_time = mock_env().block.time.seconds();
+    let msg = ExecuteMsg::FundStream {
+        start_time,
+        end_time: start_time + 100,
+    };
+
+    let info = mock_info(
+        "policy",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1u128),
+        }],
+    );
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+}
+
+#[test]
+fn fails_if_invalid_schedule() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let now = mock_env().block.time.seconds();
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1u128),
+        }],
+    );
+
+    let msg = ExecuteMsg::FundStream {
+        start_time: now - 1,
+        end_time: now + 100,
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InvalidSchedule {
+            start_time: now - 1,
+            end_time: now + 100,
+        }
+    );
+
+    let msg = ExecuteMsg::FundStream {
+        start_time: now + 100,
+        end_time: now + 100,
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InvalidSchedule {
+            start_time: now + 100,
+            end_time: now + 100,
+        }
+    );
+}
+
+#[test]
+fn add_reward_schedule() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::Token {
+        contract_addr: String::from("reward_token"),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let start_time = mock_env().block.time.seconds() + 10;
+    let end_time = start_time + 100;
+    let reward_amount = Uint128::from(1000u128);
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "funder".to_string(),
+        msg: to_binary(&Cw20HookMsg::FundStream {
+            start_time,
+            end_time,
+        })
+        .unwrap(),
+        amount: reward_amount,
+    });
+
+    let info = mock_info("reward_token", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "fund_stream"),
+            attr("funder", "funder"),
+            attr("amount", reward_amount),
+            attr("tax_amount", "0"),
+            attr("start_time", start_time.to_string()),
+            attr("end_time", end_time.to_string()),
+        ]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::RewardSchedules {}).unwrap();
+
+    let schedules_res: RewardSchedulesResponse = from_binary(&res).unwrap();
+
+    assert_eq!(
+        RewardSchedulesResponse {
+            schedules: vec![RewardSchedule {
+                reward_index: 0,
+                start_time,
+                end_time,
+                amount: reward_amount,
+                released_amount: Uint128::zero(),
+            }],
+        },
+        schedules_res
+    );
+}
+
+#[test]
+fn release_rewards_linearly() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let stake_amount = Uint128::from(100u128);
+
+    deposit(&mut deps, stake_amount).unwrap();
+
+    let start_time = mock_env().block.time.seconds();
+    let reward_amount = Uint128::from(1000u128);
+
+    let msg = ExecuteMsg::FundStream {
+        start_time,
+        end_time: start_time + 100,
+    };
+
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: reward_amount,
+        }],
+    );
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env_at(start_time + 30),
+        QueryMsg::UserInfo {
+            user: "addr".to_string(),
+        },
+    )
+    .unwrap();
+
+    let user_info_res: UserInfoResponse = from_binary(&res).unwrap();
+
+    assert_eq!(
+        UserInfoResponse {
+            stake_amount,
+            pending_rewards: vec![Asset {
+                info: reward_asset_info.clone(),
+                amount: Uint128::from(300u128),
+            }],
+            auto_compound: false,
+        },
+        user_info_res
+    );
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at(start_time + 50),
+        info.clone(),
+        ExecuteMsg::Claim {
+            recipient: None,
+            swap: None,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("addr"),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(500u128),
+            }]
+        })),]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at(start_time + 200),
+        info,
+        ExecuteMsg::Claim {
+            recipient: None,
+            swap: None,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("addr"),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(500u128),
+            }]
+        })),]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::RewardSchedules {}).unwrap();
+
+    let schedules_res: RewardSchedulesResponse = from_binary(&res).unwrap();
+
+    assert_eq!(RewardSchedulesResponse { schedules: vec![] }, schedules_res);
+}
+
+#[test]
+fn carry_over_emission_without_deposits() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let start_time = mock_env().block.time.seconds();
+    let reward_amount = Uint128::from(1000u128);
+
+    let msg = ExecuteMsg::FundStream {
+        start_time,
+        end_time: start_time + 100,
+    };
+
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: reward_amount,
+        }],
+    );
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let stake_amount = Uint128::from(100u128);
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        msg: to_binary(&Cw20HookMsg::Deposit {
+            recipient: None,
+            lock_duration: None,
+        })
+        .unwrap(),
+        amount: stake_amount,
+    });
+
+    let info = mock_info("staking_token", &[]);
+
+    execute(deps.as_mut(), mock_env_at(start_time + 40), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env_at(start_time + 100),
+        QueryMsg::UserInfo {
+            user: "addr".to_string(),
+        },
+    )
+    .unwrap();
+
+    let user_info_res: UserInfoResponse = from_binary(&res).unwrap();
+
+    assert_eq!(
+        UserInfoResponse {
+            stake_amount,
+            pending_rewards: vec![Asset {
+                info: reward_asset_info.clone(),
+                amount: reward_amount,
+            }],
+            auto_compound: false,
+        },
+        user_info_res
+    );
+}
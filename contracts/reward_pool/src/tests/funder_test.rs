@@ -0,0 +1,256 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{from_binary, Coin, OwnedDeps, Uint128};
+
+use reward_pool::reward_pool::{ExecuteMsg, FunderResponse, FundersResponse, FundingCap, QueryMsg};
+use terraswap::asset::{Asset, AssetInfo};
+
+use crate::{
+    contract::{execute, query},
+    error::ContractError,
+    tests::{
+        mock_querier::{mock_dependencies, WasmMockQuerier},
+        test_utils::{instantiate_reward_pool, mock_env_at, uusd},
+    },
+};
+
+fn update_funder(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    funder: &str,
+    enabled: bool,
+    caps: Vec<FundingCap>,
+) -> Result<(), ContractError> {
+    let msg = ExecuteMsg::UpdateFunder {
+        funder: funder.to_string(),
+        enabled,
+        caps,
+    };
+
+    let info = mock_info("governance", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg)?;
+
+    Ok(())
+}
+
+fn fund_at(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    funder: &str,
+    amount: u128,
+    time: u64,
+) -> Result<(), ContractError> {
+    let info = mock_info(
+        funder,
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(amount),
+        }],
+    );
+
+    execute(deps.as_mut(), mock_env_at(time), info, ExecuteMsg::Fund {})?;
+
+    Ok(())
+}
+
+fn query_funders(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Vec<FunderResponse> {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Funders { start_after, limit },
+    )
+    .unwrap();
+
+    let funders_res: FundersResponse = from_binary(&res).unwrap();
+    funders_res.funders
+}
+
+#[test]
+fn fails_if_caller_is_not_governance() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    let msg = ExecuteMsg::UpdateFunder {
+        funder: "funder1".to_string(),
+        enabled: true,
+        caps: vec![],
+    };
+
+    let info = mock_info("funder", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+}
+
+#[test]
+fn fails_if_cap_is_invalid() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    let ukrw = AssetInfo::NativeToken {
+        denom: "ukrw".to_string(),
+    };
+    let res = update_funder(
+        &mut deps,
+        "funder1",
+        true,
+        vec![FundingCap {
+            asset_info: ukrw.clone(),
+            period: 100,
+            amount: Uint128::from(100u128),
+        }],
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::UnsupportedRewardAsset {
+            asset_info: ukrw.to_string(),
+        }
+    );
+
+    let res = update_funder(
+        &mut deps,
+        "funder1",
+        true,
+        vec![FundingCap {
+            asset_info: uusd(),
+            period: 0,
+            amount: Uint128::from(100u128),
+        }],
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InvalidFundingCap {
+            asset_info: uusd().to_string(),
+        }
+    );
+}
+
+#[test]
+fn fund_from_multiple_funders() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    let cap = FundingCap {
+        asset_info: uusd(),
+        period: 100,
+        amount: Uint128::from(1000u128),
+    };
+    update_funder(&mut deps, "funder1", true, vec![cap.clone()]).unwrap();
+
+    let now = mock_env().block.time.seconds();
+    fund_at(&mut deps, "funder", 100, now).unwrap();
+    fund_at(&mut deps, "funder1", 200, now).unwrap();
+
+    let mut funders = query_funders(&deps, None, None);
+    funders.sort_by(|a, b| a.funder.cmp(&b.funder));
+    assert_eq!(
+        funders,
+        vec![
+            FunderResponse {
+                funder: "funder".to_string(),
+                enabled: true,
+                caps: vec![],
+                funded_amounts: vec![Asset {
+                    info: uusd(),
+                    amount: Uint128::from(100u128),
+                }],
+            },
+            FunderResponse {
+                funder: "funder1".to_string(),
+                enabled: true,
+                caps: vec![cap],
+                funded_amounts: vec![Asset {
+                    info: uusd(),
+                    amount: Uint128::from(200u128),
+                }],
+            },
+        ]
+    );
+}
+
+#[test]
+fn fails_if_funder_is_disabled() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    update_funder(&mut deps, "funder", false, vec![]).unwrap();
+
+    let now = mock_env().block.time.seconds();
+    let res = fund_at(&mut deps, "funder", 100, now).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    update_funder(&mut deps, "funder", true, vec![]).unwrap();
+
+    fund_at(&mut deps, "funder", 100, now).unwrap();
+}
+
+#[test]
+fn fails_if_cap_is_exceeded() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    update_funder(
+        &mut deps,
+        "funder",
+        true,
+        vec![FundingCap {
+            asset_info: uusd(),
+            period: 100,
+            amount: Uint128::from(100u128),
+        }],
+    )
+    .unwrap();
+
+    let now = mock_env().block.time.seconds();
+    fund_at(&mut deps, "funder", 60, now).unwrap();
+
+    let res = fund_at(&mut deps, "funder", 50, now + 99).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::FundingCapExceeded {
+            requested: Uint128::from(50u128),
+            remaining: Uint128::from(40u128),
+        }
+    );
+
+    fund_at(&mut deps, "funder", 40, now + 99).unwrap();
+    fund_at(&mut deps, "funder", 100, now + 100).unwrap();
+
+    let funders = query_funders(&deps, None, None);
+    assert_eq!(
+        funders[0].funded_amounts,
+        vec![Asset {
+            info: uusd(),
+            amount: Uint128::from(200u128),
+        }]
+    );
+}
+
+#[test]
+fn paginate_funders() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    for funder in ["funder1", "funder2", "funder3"] {
+        update_funder(&mut deps, funder, true, vec![]).unwrap();
+    }
+
+    let funders = query_funders(&deps, None, None);
+    assert_eq!(funders.len(), 4);
+
+    let page = query_funders(&deps, None, Some(2));
+    assert_eq!(page, funders[..2].to_vec());
+
+    let page = query_funders(&deps, Some(page[1].funder.clone()), Some(2));
+    assert_eq!(page, funders[2..].to_vec());
+}
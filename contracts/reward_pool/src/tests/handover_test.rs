@@ -0,0 +1,193 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{from_binary, OverflowError, OverflowOperation, OwnedDeps, StdError};
+
+use reward_pool::reward_pool::{ConfigResponse, ExecuteMsg, PendingHandover, QueryMsg};
+use terraswap::asset::AssetInfo;
+
+use crate::{
+    contract::{execute, query},
+    error::ContractError,
+    tests::{
+        mock_querier::{mock_dependencies, WasmMockQuerier},
+        test_utils::{instantiate_reward_pool, mock_env_at},
+    },
+};
+
+const EXPIRES_IN: u64 = 100;
+
+fn query_config(deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> ConfigResponse {
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+
+    from_binary(&res).unwrap()
+}
+
+fn propose_governance(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let msg = ExecuteMsg::ProposeGovernance {
+        governance: String::from("governance1"),
+        expires_in: EXPIRES_IN,
+    };
+
+    let info = mock_info("governance", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+#[test]
+fn fails_to_propose_if_caller_is_not_governance() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    let msgs = vec![
+        ExecuteMsg::ProposeGovernance {
+            governance: String::from("governance1"),
+            expires_in: EXPIRES_IN,
+        },
+        ExecuteMsg::CancelGovernanceProposal {},
+    ];
+
+    for msg in msgs {
+        let info = mock_info("funder", &[]);
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+    }
+}
+
+#[test]
+fn propose_and_accept_governance() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    propose_governance(&mut deps);
+
+    let config_res = query_config(&deps);
+    assert_eq!(config_res.governance, String::from("governance"));
+    assert_eq!(
+        config_res.pending_governance,
+        Some(PendingHandover {
+            address: String::from("governance1"),
+            expires_at: mock_env().block.time.seconds() + EXPIRES_IN,
+        })
+    );
+
+    let msg = ExecuteMsg::AcceptGovernance {};
+
+    let info = mock_info("governance1", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let config_res = query_config(&deps);
+    assert_eq!(config_res.governance, String::from("governance1"));
+    assert_eq!(config_res.pending_governance, None);
+}
+
+#[test]
+fn fails_to_accept_if_caller_is_not_nominee() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    propose_governance(&mut deps);
+
+    let msg = ExecuteMsg::AcceptGovernance {};
+
+    let info = mock_info("governance", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+}
+
+#[test]
+fn fails_to_accept_if_expired() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    propose_governance(&mut deps);
+
+    let msg = ExecuteMsg::AcceptGovernance {};
+
+    let info = mock_info("governance1", &[]);
+
+    let expires_at = mock_env().block.time.seconds() + EXPIRES_IN;
+    let res = execute(deps.as_mut(), mock_env_at(expires_at), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::HandoverExpired { expires_at });
+}
+
+#[test]
+fn fails_to_propose_if_expiry_overflows() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    let msg = ExecuteMsg::ProposeGovernance {
+        governance: String::from("governance1"),
+        expires_in: u64::MAX,
+    };
+
+    let info = mock_info("governance", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::Std(StdError::overflow(OverflowError::new(
+            OverflowOperation::Add,
+            mock_env().block.time.seconds(),
+            u64::MAX,
+        )))
+    );
+    assert_eq!(query_config(&deps).pending_governance, None);
+}
+
+#[test]
+fn cancel_governance_proposal() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    propose_governance(&mut deps);
+
+    let msg = ExecuteMsg::CancelGovernanceProposal {};
+
+    let info = mock_info("governance", &[]);
+
+    execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+
+    assert_eq!(query_config(&deps).pending_governance, None);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::NoPendingHandover {});
+
+    let msg = ExecuteMsg::AcceptGovernance {};
+
+    let info = mock_info("governance1", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::NoPendingHandover {});
+}
@@ -0,0 +1,284 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{
+    attr, coin, from_binary, to_binary, ContractResult, OwnedDeps, Reply, SubMsg, Uint128,
+};
+
+use cw20::Cw20ReceiveMsg;
+use reward_pool::reward_pool::{
+    Cw20HookMsg, ExecuteMsg, HooksResponse, QueryMsg, StakeChangedHookMsg, UserInfoResponse,
+};
+use terraswap::asset::AssetInfo;
+
+use crate::{
+    contract::{execute, query, reply},
+    error::ContractError,
+    execute::STAKE_CHANGED_HOOK_REPLY_ID,
+    tests::{
+        mock_querier::{mock_dependencies, WasmMockQuerier},
+        test_utils::{deposit, instantiate_reward_pool},
+    },
+};
+
+fn add_hook(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, addr: &str) {
+    let msg = ExecuteMsg::AddHook {
+        addr: addr.to_string(),
+    };
+
+    let info = mock_info("governance", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+fn hook_messages(user: &str, old_stake: u128, new_stake: u128) -> Vec<SubMsg> {
+    ["gov", "boost"]
+        .iter()
+        .map(|hook| {
+            SubMsg::new(
+                StakeChangedHookMsg {
+                    user: user.to_string(),
+                    old_stake: Uint128::from(old_stake),
+                    new_stake: Uint128::from(new_stake),
+                }
+                .into_cosmos_msg(hook.to_string())
+                .unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn fails_if_caller_is_not_governance() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    let info = mock_info("addr", &[]);
+
+    for msg in [
+        ExecuteMsg::AddHook {
+            addr: "gov".to_string(),
+        },
+        ExecuteMsg::RemoveHook {
+            addr: "gov".to_string(),
+        },
+    ] {
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+    }
+}
+
+#[test]
+fn add_and_remove_hooks() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    let msg = ExecuteMsg::AddHook {
+        addr: "gov".to_string(),
+    };
+
+    let info = mock_info("governance", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "add_hook"), attr("addr", "gov")]
+    );
+
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::HookAlreadyRegistered {
+            addr: "gov".to_string()
+        }
+    );
+
+    add_hook(&mut deps, "boost");
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Hooks {}).unwrap();
+
+    let hooks_res: HooksResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        hooks_res.hooks,
+        vec!["gov".to_string(), "boost".to_string()]
+    );
+
+    let msg = ExecuteMsg::RemoveHook {
+        addr: "gov".to_string(),
+    };
+
+    execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::HookNotRegistered {
+            addr: "gov".to_string()
+        }
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Hooks {}).unwrap();
+
+    let hooks_res: HooksResponse = from_binary(&res).unwrap();
+    assert_eq!(hooks_res.hooks, vec!["boost".to_string()]);
+}
+
+#[test]
+fn notify_hooks_on_stake_change() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    add_hook(&mut deps, "gov");
+    add_hook(&mut deps, "boost");
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: Uint128::from(30u128),
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(res.messages[1..].to_vec(), hook_messages("addr", 100, 70));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::EmergencyWithdraw {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[1..].to_vec(),
+        hook_messages("addr", 70, 0)
+            .into_iter()
+            .map(|sub_msg| SubMsg::reply_on_error(sub_msg.msg, STAKE_CHANGED_HOOK_REPLY_ID))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn ignore_hook_failure_on_emergency_withdraw() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    add_hook(&mut deps, "gov");
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let info = mock_info("addr", &[]);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::EmergencyWithdraw {},
+    )
+    .unwrap();
+
+    let msg = Reply {
+        id: STAKE_CHANGED_HOOK_REPLY_ID,
+        result: ContractResult::Err("hook failed".to_string()),
+    };
+
+    let res = reply(deps.as_mut(), mock_env(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "ignore_hook_failure"),
+            attr("error", "hook failed"),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UserInfo {
+            user: "addr".to_string(),
+        },
+    )
+    .unwrap();
+
+    let user_info_res: UserInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(user_info_res.stake_amount, Uint128::zero());
+}
+
+#[test]
+fn notify_hooks_on_deposit() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    add_hook(&mut deps, "gov");
+    add_hook(&mut deps, "boost");
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        msg: to_binary(&Cw20HookMsg::Deposit {
+            recipient: None,
+            lock_duration: None,
+        })
+        .unwrap(),
+        amount: Uint128::from(50u128),
+    });
+
+    let info = mock_info("staking_token", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(res.messages, hook_messages("addr", 100, 150));
+}
+
+#[test]
+fn do_not_notify_hooks_if_stake_is_unchanged() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    add_hook(&mut deps, "gov");
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let info = mock_info("funder", &[coin(100, "uusd")]);
+
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+
+    let msg = ExecuteMsg::Claim {
+        recipient: None,
+        swap: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(res.messages.len(), 1);
+}
@@ -1,56 +1,194 @@
-use cosmwasm_std::testing::{mock_env, mock_info};
-use cosmwasm_std::{from_binary, Decimal, Uint128};
-
-use reward_pool::reward_pool::{ConfigResponse, InstantiateMsg, QueryMsg, State};
-use terraswap::asset::AssetInfo;
-
-use crate::{
-    contract::{instantiate, query},
-    tests::mock_querier::mock_dependencies,
-};
-
-#[test]
-fn instantiate_reward_pool() {
-    let mut deps = mock_dependencies(&[]);
-
-    let reward_asset_info = AssetInfo::NativeToken {
-        denom: "uusd".to_string(),
-    };
-
-    let msg = InstantiateMsg {
-        governance: String::from("governance"),
-        funder: String::from("funder"),
-        staking_token: String::from("staking_token"),
-        reward_asset_info: reward_asset_info.clone(),
-    };
-
-    let info = mock_info("policy", &[]);
-
-    instantiate(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
-
-    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
-
-    let config_res: ConfigResponse = from_binary(&res).unwrap();
-
-    assert_eq!(
-        ConfigResponse {
-            governance: String::from("governance"),
-            funder: String::from("funder"),
-            staking_token: String::from("staking_token"),
-            reward_asset_info,
-        },
-        config_res
-    );
-
-    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
-
-    let state_res: State = from_binary(&res).unwrap();
-
-    assert_eq!(
-        State {
-            acc_per_share: Decimal::zero(),
-            total_deposits: Uint128::zero(),
-        },
-        state_res
-    );
-}
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, Decimal256, Uint128};
+
+use reward_pool::reward_pool::{
+    ConfigResponse, ForfeitMode, InstantiateMsg, LockTier, PauseFlags, QueryMsg, RewardState, State,
+};
+use terraswap::asset::{Asset, AssetInfo};
+
+use crate::{
+    contract::{instantiate, query},
+    error::ContractError,
+    tests::mock_querier::mock_dependencies,
+};
+
+#[test]
+fn instantiate_reward_pool() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    let msg = InstantiateMsg {
+        governance: String::from("governance"),
+        guardian: None,
+        funders: vec![String::from("funder")],
+        staking_asset_info: AssetInfo::Token {
+            contract_addr: String::from("staking_token"),
+        },
+        reward_asset_infos: vec![reward_asset_info.clone()],
+        lock_tiers: vec![LockTier {
+            duration: 86400,
+            boost: Decimal256::percent(150),
+        }],
+        unbonding_period: 0,
+        forfeit_mode: ForfeitMode::Redistribute,
+        permissionless_funding: false,
+        min_fund_amounts: vec![],
+        cw4_enabled: false,
+        tokens_per_weight: Uint128::from(1u128),
+        receipt_token: None,
+    };
+
+    let info = mock_info("policy", &[]);
+
+    instantiate(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+
+    assert_eq!(
+        ConfigResponse {
+            governance: String::from("governance"),
+            pending_governance: None,
+            guardian: None,
+            staking_asset_info: AssetInfo::Token {
+                contract_addr: String::from("staking_token"),
+            },
+            reward_asset_infos: vec![reward_asset_info],
+            lock_tiers: msg.lock_tiers,
+            unbonding_period: 0,
+            forfeit_mode: ForfeitMode::Redistribute,
+            permissionless_funding: false,
+            min_fund_amounts: vec![],
+            cw4_enabled: false,
+            tokens_per_weight: Uint128::from(1u128),
+            paused: PauseFlags::default(),
+            receipt_token: None,
+        },
+        config_res
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(
+        State {
+            total_deposits: Uint128::zero(),
+            total_weight: Uint128::zero(),
+            total_unbonding: Uint128::zero(),
+            staker_count: 0,
+            rewards: vec![RewardState {
+                acc_per_share: Decimal256::zero(),
+                undistributed_amount: Uint128::zero(),
+                distributed_amount: Uint128::zero(),
+                claimed_amount: Uint128::zero(),
+                forfeited_amount: Uint128::zero(),
+                tax_reserve: Uint128::zero(),
+            }],
+        },
+        state_res
+    );
+}
+
+#[test]
+fn fails_if_lock_tier_is_invalid() {
+    let mut deps = mock_dependencies(&[]);
+
+    let invalid_lock_tiers = vec![
+        vec![LockTier {
+            duration: 0,
+            boost: Decimal256::percent(150),
+        }],
+        vec![LockTier {
+            duration: 86400,
+            boost: Decimal256::percent(50),
+        }],
+        vec![
+            LockTier {
+                duration: 86400,
+                boost: Decimal256::percent(150),
+            },
+            LockTier {
+                duration: 86400,
+                boost: Decimal256::percent(200),
+            },
+        ],
+    ];
+
+    for lock_tiers in invalid_lock_tiers {
+        let duration = lock_tiers.last().unwrap().duration;
+        let msg = InstantiateMsg {
+            governance: String::from("governance"),
+            guardian: None,
+            funders: vec![String::from("funder")],
+            staking_asset_info: AssetInfo::Token {
+                contract_addr: String::from("staking_token"),
+            },
+            reward_asset_infos: vec![AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            }],
+            lock_tiers,
+            unbonding_period: 0,
+            forfeit_mode: ForfeitMode::Redistribute,
+            permissionless_funding: false,
+            min_fund_amounts: vec![],
+            cw4_enabled: false,
+            tokens_per_weight: Uint128::from(1u128),
+            receipt_token: None,
+        };
+
+        let info = mock_info("policy", &[]);
+
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidLockTier { duration });
+    }
+}
+
+#[test]
+fn fails_if_permissionless_without_min_fund_amount() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        governance: String::from("governance"),
+        guardian: None,
+        funders: vec![String::from("funder")],
+        staking_asset_info: AssetInfo::Token {
+            contract_addr: String::from("staking_token"),
+        },
+        reward_asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: String::from("reward_token"),
+            },
+        ],
+        lock_tiers: vec![],
+        unbonding_period: 0,
+        forfeit_mode: ForfeitMode::Redistribute,
+        permissionless_funding: true,
+        min_fund_amounts: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::from(100u128),
+        }],
+        cw4_enabled: false,
+        tokens_per_weight: Uint128::from(1u128),
+        receipt_token: None,
+    };
+
+    let info = mock_info("policy", &[]);
+
+    let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::MinFundAmountRequired {
+            asset_info: "reward_token".to_string()
+        }
+    );
+}
@@ -0,0 +1,522 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{from_binary, to_binary, Coin, Decimal256, Env, OwnedDeps, Uint128};
+
+use cw20::Cw20ReceiveMsg;
+use reward_pool::reward_pool::{
+    Cw20HookMsg, ExecuteMsg, LockPosition, LocksResponse, QueryMsg, State, UserInfoResponse,
+};
+use terraswap::asset::AssetInfo;
+
+use crate::{
+    contract::{execute, query},
+    error::ContractError,
+    tests::{
+        mock_querier::{mock_dependencies, WasmMockQuerier},
+        test_utils::{instantiate_reward_pool, mock_env_at, query_pending_reward, MONTH},
+    },
+    utils::MAX_LOCK_POSITIONS,
+};
+
+fn deposit_locked(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    env: Env,
+    user: &str,
+    amount: Uint128,
+    lock_duration: Option<u64>,
+) -> Result<(), ContractError> {
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: user.to_string(),
+        msg: to_binary(&Cw20HookMsg::Deposit {
+            recipient: None,
+            lock_duration,
+        })
+        .unwrap(),
+        amount,
+    });
+
+    let info = mock_info("staking_token", &[]);
+
+    execute(deps.as_mut(), env, info, msg)?;
+
+    Ok(())
+}
+
+fn query_locks(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    user: &str,
+) -> Vec<LockPosition> {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Locks {
+            user: user.to_string(),
+        },
+    )
+    .unwrap();
+
+    let locks_res: LocksResponse = from_binary(&res).unwrap();
+    locks_res.locks
+}
+
+#[test]
+fn fails_if_lock_duration_is_invalid() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let res = deposit_locked(
+        &mut deps,
+        mock_env(),
+        "addr",
+        Uint128::from(100u128),
+        Some(2 * MONTH),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InvalidLockDuration {
+            duration: 2 * MONTH
+        }
+    );
+}
+
+#[test]
+fn fails_to_lock_deposit_for_other_user() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        msg: to_binary(&Cw20HookMsg::Deposit {
+            recipient: Some("holder".to_string()),
+            lock_duration: Some(MONTH),
+        })
+        .unwrap(),
+        amount: Uint128::from(100u128),
+    });
+
+    let info = mock_info("staking_token", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::CannotLockForOther {});
+    assert_eq!(query_locks(&deps, "holder"), vec![]);
+}
+
+#[test]
+fn create_lock_positions() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let now = mock_env().block.time.seconds();
+
+    deposit_locked(
+        &mut deps,
+        mock_env(),
+        "addr",
+        Uint128::from(100u128),
+        Some(MONTH),
+    )
+    .unwrap();
+    deposit_locked(
+        &mut deps,
+        mock_env(),
+        "addr",
+        Uint128::from(50u128),
+        Some(12 * MONTH),
+    )
+    .unwrap();
+    deposit_locked(&mut deps, mock_env(), "addr", Uint128::from(10u128), None).unwrap();
+
+    assert_eq!(
+        query_locks(&deps, "addr"),
+        vec![
+            LockPosition {
+                amount: Uint128::from(100u128),
+                boost: Decimal256::percent(150),
+                start_time: now,
+                end_time: now + MONTH,
+            },
+            LockPosition {
+                amount: Uint128::from(50u128),
+                boost: Decimal256::percent(300),
+                start_time: now,
+                end_time: now + 12 * MONTH,
+            },
+        ]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(state_res.total_deposits, Uint128::from(160u128));
+    assert_eq!(state_res.total_weight, Uint128::from(310u128));
+}
+
+#[test]
+fn merge_lock_positions_with_same_end_time() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let now = mock_env().block.time.seconds();
+
+    deposit_locked(
+        &mut deps,
+        mock_env(),
+        "addr",
+        Uint128::from(100u128),
+        Some(MONTH),
+    )
+    .unwrap();
+    deposit_locked(
+        &mut deps,
+        mock_env(),
+        "addr",
+        Uint128::from(50u128),
+        Some(MONTH),
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_locks(&deps, "addr"),
+        vec![LockPosition {
+            amount: Uint128::from(150u128),
+            boost: Decimal256::percent(150),
+            start_time: now,
+            end_time: now + MONTH,
+        }]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(state_res.total_deposits, Uint128::from(150u128));
+    assert_eq!(state_res.total_weight, Uint128::from(225u128));
+}
+
+#[test]
+fn fails_to_open_too_many_lock_positions() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let now = mock_env().block.time.seconds();
+
+    for index in 0..MAX_LOCK_POSITIONS as u64 {
+        deposit_locked(
+            &mut deps,
+            mock_env_at(now + index),
+            "addr",
+            Uint128::from(10u128),
+            Some(MONTH),
+        )
+        .unwrap();
+    }
+    assert_eq!(query_locks(&deps, "addr").len(), MAX_LOCK_POSITIONS);
+
+    // a position with the same end time is still merged
+    deposit_locked(
+        &mut deps,
+        mock_env_at(now),
+        "addr",
+        Uint128::from(10u128),
+        Some(MONTH),
+    )
+    .unwrap();
+    assert_eq!(query_locks(&deps, "addr")[0].amount, Uint128::from(20u128));
+
+    // the first position is released at its end time
+    deposit_locked(
+        &mut deps,
+        mock_env_at(now + MONTH),
+        "addr",
+        Uint128::from(10u128),
+        Some(MONTH),
+    )
+    .unwrap();
+    assert_eq!(query_locks(&deps, "addr").len(), MAX_LOCK_POSITIONS);
+
+    let res = deposit_locked(
+        &mut deps,
+        mock_env_at(now + MONTH),
+        "addr",
+        Uint128::from(10u128),
+        Some(12 * MONTH),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::TooManyLocks {
+            max: MAX_LOCK_POSITIONS
+        }
+    );
+}
+
+#[test]
+fn boost_reward_share_of_locked_stake() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    deposit_locked(
+        &mut deps,
+        mock_env(),
+        "addr",
+        Uint128::from(100u128),
+        Some(12 * MONTH),
+    )
+    .unwrap();
+    deposit_locked(&mut deps, mock_env(), "other", Uint128::from(100u128), None).unwrap();
+
+    let msg = ExecuteMsg::Fund {};
+
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(400u128),
+        }],
+    );
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    for (user, pending_amount) in [("addr", 300u128), ("other", 100u128)] {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::UserInfo {
+                user: user.to_string(),
+            },
+        )
+        .unwrap();
+
+        let user_info_res: UserInfoResponse = from_binary(&res).unwrap();
+
+        assert_eq!(
+            user_info_res.pending_rewards[0].amount,
+            Uint128::from(pending_amount)
+        );
+    }
+}
+
+#[test]
+fn fails_to_withdraw_locked_stake() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    deposit_locked(&mut deps, mock_env(), "addr", Uint128::from(100u128), None).unwrap();
+    deposit_locked(
+        &mut deps,
+        mock_env(),
+        "addr",
+        Uint128::from(100u128),
+        Some(MONTH),
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: Uint128::from(150u128),
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let now = mock_env().block.time.seconds();
+    let res = execute(deps.as_mut(), mock_env_at(now + MONTH - 1), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InsufficientStake {
+            requested: Uint128::from(150u128),
+            available: Uint128::from(100u128),
+        }
+    );
+}
+
+#[test]
+fn withdraw_after_lock_expires() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    deposit_locked(&mut deps, mock_env(), "addr", Uint128::from(100u128), None).unwrap();
+    deposit_locked(
+        &mut deps,
+        mock_env(),
+        "addr",
+        Uint128::from(100u128),
+        Some(MONTH),
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: Uint128::from(150u128),
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let now = mock_env().block.time.seconds();
+    execute(deps.as_mut(), mock_env_at(now + MONTH), info, msg).unwrap();
+
+    assert_eq!(query_locks(&deps, "addr"), vec![]);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(state_res.total_deposits, Uint128::from(50u128));
+    assert_eq!(state_res.total_weight, Uint128::from(50u128));
+}
+
+#[test]
+fn drop_boost_at_lock_end_time() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    deposit_locked(
+        &mut deps,
+        mock_env(),
+        "addr",
+        Uint128::from(100u128),
+        Some(MONTH),
+    )
+    .unwrap();
+    deposit_locked(&mut deps, mock_env(), "other", Uint128::from(100u128), None).unwrap();
+
+    let msg = ExecuteMsg::Fund {};
+
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(200u128),
+        }],
+    );
+
+    let env = mock_env_at(mock_env().block.time.seconds() + MONTH);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    assert_eq!(
+        query_pending_reward(&deps, env.clone(), "addr"),
+        Uint128::from(100u128)
+    );
+    assert_eq!(
+        query_pending_reward(&deps, env, "other"),
+        Uint128::from(100u128)
+    );
+    assert_eq!(query_locks(&deps, "addr"), vec![]);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(state_res.total_deposits, Uint128::from(200u128));
+    assert_eq!(state_res.total_weight, Uint128::from(200u128));
+}
+
+#[test]
+fn drop_boost_of_streamed_rewards_at_lock_end_time() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::Token {
+        contract_addr: String::from("reward_token"),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    deposit_locked(
+        &mut deps,
+        mock_env(),
+        "addr",
+        Uint128::from(100u128),
+        Some(MONTH),
+    )
+    .unwrap();
+    deposit_locked(&mut deps, mock_env(), "other", Uint128::from(100u128), None).unwrap();
+
+    let now = mock_env().block.time.seconds();
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "funder".to_string(),
+        msg: to_binary(&Cw20HookMsg::FundStream {
+            start_time: now,
+            end_time: now + 2 * MONTH,
+        })
+        .unwrap(),
+        amount: Uint128::from(300u128),
+    });
+
+    let info = mock_info("reward_token", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // 150 are shared 150:100 until the end of the lock, the rest 100:100
+    let env = mock_env_at(now + 2 * MONTH);
+    assert_eq!(
+        query_pending_reward(&deps, env.clone(), "addr"),
+        Uint128::from(165u128)
+    );
+    assert_eq!(
+        query_pending_reward(&deps, env.clone(), "other"),
+        Uint128::from(135u128)
+    );
+
+    deposit_locked(
+        &mut deps,
+        env.clone(),
+        "other",
+        Uint128::from(100u128),
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(
+        query_pending_reward(&deps, env.clone(), "addr"),
+        Uint128::from(165u128)
+    );
+    assert_eq!(
+        query_pending_reward(&deps, env, "other"),
+        Uint128::from(135u128)
+    );
+}
@@ -0,0 +1,393 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{
+    attr, coin, from_binary, Api, BankMsg, CosmosMsg, Decimal, OwnedDeps, StdResult, SubMsg,
+    Uint128,
+};
+
+use reward_pool::reward_pool::{
+    AllUsersResponse, ConfigResponse, ExecuteMsg, FundersResponse, MigrateMsg, QueryMsg,
+    StakeAtHeightResponse, State, UserInfoResponse,
+};
+use terraswap::asset::AssetInfo;
+
+use crate::{
+    contract::{execute, migrate, query},
+    error::ContractError,
+    migration::{
+        LegacyConfig, LegacyState, LegacyUserInfo, LEGACY_CONFIGURATION, LEGACY_STATE,
+        LEGACY_USER_INFO,
+    },
+    state::{UserInfo, STATE, USER_INFO},
+    tests::{
+        mock_querier::{mock_dependencies, WasmMockQuerier},
+        test_utils::{deposit_at, instantiate_reward_pool, mock_env_at_height},
+    },
+};
+
+fn save_legacy_storage(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    users: Vec<(String, LegacyUserInfo)>,
+) {
+    LEGACY_CONFIGURATION
+        .save(
+            &mut deps.storage,
+            &LegacyConfig {
+                governance: deps.api.addr_canonicalize("governance").unwrap(),
+                funder: deps.api.addr_canonicalize("funder").unwrap(),
+                staking_token: deps.api.addr_canonicalize("staking_token").unwrap(),
+                reward_asset_info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                }
+                .to_raw(&deps.api)
+                .unwrap(),
+            },
+        )
+        .unwrap();
+
+    let mut total_deposits = Uint128::zero();
+    for (user, legacy_user_info) in users {
+        total_deposits += legacy_user_info.stake_amount;
+        let user_raw = deps.api.addr_canonicalize(&user).unwrap();
+        LEGACY_USER_INFO
+            .save(&mut deps.storage, user_raw.as_slice(), &legacy_user_info)
+            .unwrap();
+    }
+    LEGACY_STATE
+        .save(
+            &mut deps.storage,
+            &LegacyState {
+                acc_per_share: Decimal::from_ratio(2u128, 1u128),
+                total_deposits,
+            },
+        )
+        .unwrap();
+}
+
+#[test]
+fn migrate_legacy_storage() {
+    let mut deps = mock_dependencies(&[]);
+
+    save_legacy_storage(
+        &mut deps,
+        vec![
+            (
+                "addr".to_string(),
+                LegacyUserInfo {
+                    stake_amount: Uint128::from(100u128),
+                    pending_amount: Uint128::from(50u128),
+                    reward_debt: Uint128::from(100u128),
+                },
+            ),
+            (
+                "holder".to_string(),
+                LegacyUserInfo {
+                    stake_amount: Uint128::from(50u128),
+                    pending_amount: Uint128::zero(),
+                    reward_debt: Uint128::from(100u128),
+                },
+            ),
+        ],
+    );
+
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate"),
+            attr("migrated_users", "2"),
+            attr("completed", "true")
+        ]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config_res.governance, "governance".to_string());
+    assert_eq!(
+        config_res.staking_asset_info,
+        AssetInfo::Token {
+            contract_addr: "staking_token".to_string(),
+        }
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Funders {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+
+    let funders_res: FundersResponse = from_binary(&res).unwrap();
+    assert_eq!(funders_res.funders[0].funder, "funder".to_string());
+    assert!(funders_res.funders[0].enabled);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+    assert_eq!(state_res.total_weight, Uint128::from(150u128));
+    assert_eq!(state_res.staker_count, 2);
+    assert_eq!(
+        state_res.rewards[0].distributed_amount,
+        Uint128::from(150u128)
+    );
+
+    let height = mock_env().block.height + 1;
+    for (user, stake_amount) in [("addr", 100u128), ("holder", 50)] {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::UserStakeAtHeight {
+                user: user.to_string(),
+                height,
+            },
+        )
+        .unwrap();
+
+        let stake_res: StakeAtHeightResponse = from_binary(&res).unwrap();
+        assert_eq!(stake_res.stake_amount, Uint128::from(stake_amount));
+    }
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::TotalStakeAtHeight { height },
+    )
+    .unwrap();
+
+    let stake_res: StakeAtHeightResponse = from_binary(&res).unwrap();
+    assert_eq!(stake_res.stake_amount, Uint128::from(150u128));
+
+    let info = mock_info("funder", &[coin(150, "uusd")]);
+
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UserInfo {
+            user: "holder".to_string(),
+        },
+    )
+    .unwrap();
+
+    let user_info_res: UserInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(user_info_res.stake_amount, Uint128::from(50u128));
+    assert_eq!(
+        user_info_res.pending_rewards[0].amount,
+        Uint128::from(50u128)
+    );
+
+    let msg = ExecuteMsg::Claim {
+        recipient: None,
+        swap: None,
+    };
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr".to_string(),
+            amount: vec![coin(250, "uusd")],
+        }))]
+    );
+}
+
+#[test]
+fn migrate_legacy_users_in_batches() {
+    let mut deps = mock_dependencies(&[]);
+
+    let users = (0..35)
+        .map(|index| {
+            (
+                format!("user{:02}", index),
+                LegacyUserInfo {
+                    stake_amount: Uint128::from(10u128),
+                    pending_amount: Uint128::from(1u128),
+                    reward_debt: Uint128::from(20u128),
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+    save_legacy_storage(&mut deps, users);
+
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate"),
+            attr("migrated_users", "30"),
+            attr("completed", "false")
+        ]
+    );
+
+    let info = mock_info("funder", &[coin(100, "uusd")]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap_err();
+    assert_eq!(res, ContractError::MigrationPending {});
+
+    let info = mock_info("anyone", &[]);
+    let msg = ExecuteMsg::MigrateUsers { limit: Some(3) };
+
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate_users"),
+            attr("migrated_users", "3"),
+            attr("completed", "false")
+        ]
+    );
+
+    let msg = ExecuteMsg::MigrateUsers { limit: None };
+
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "migrate_users"),
+            attr("migrated_users", "2"),
+            attr("completed", "true")
+        ]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+    assert_eq!(state_res.total_deposits, Uint128::from(350u128));
+    assert_eq!(state_res.staker_count, 35);
+    assert_eq!(
+        state_res.rewards[0].distributed_amount,
+        Uint128::from(35u128)
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AllUsers {
+            start_after: None,
+            limit: Some(30),
+        },
+    )
+    .unwrap();
+
+    let all_users_res: AllUsersResponse = from_binary(&res).unwrap();
+    assert_eq!(all_users_res.users.len(), 30);
+    assert!(all_users_res
+        .users
+        .iter()
+        .all(|user| user.stake_amount == Uint128::from(10u128)
+            && user.pending_rewards[0].amount == Uint128::from(1u128)));
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::NoPendingMigration {});
+
+    let info = mock_info("funder", &[coin(100, "uusd")]);
+
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+}
+
+#[test]
+fn keep_current_storage_on_migrate() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "migrate"), attr("seeded_snapshots", "0")]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config_res.lock_tiers.len(), 2);
+}
+
+#[test]
+fn seed_snapshots_on_migrate() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    // stakes from before the snapshots were kept
+    for (user, stake_amount) in [("addr", 100u128), ("holder", 50)] {
+        let user_raw = deps.api.addr_canonicalize(user).unwrap();
+        USER_INFO
+            .save(
+                &mut deps.storage,
+                user_raw.as_slice(),
+                &UserInfo {
+                    stake_amount: Uint128::from(stake_amount),
+                    weight: Uint128::from(stake_amount),
+                    ..UserInfo::default()
+                },
+            )
+            .unwrap();
+    }
+    STATE
+        .update(&mut deps.storage, |mut state| -> StdResult<_> {
+            state.total_deposits = Uint128::from(150u128);
+            state.total_weight = Uint128::from(150u128);
+            Ok(state)
+        })
+        .unwrap();
+
+    let res = migrate(deps.as_mut(), mock_env_at_height(200), MigrateMsg {}).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "migrate"), attr("seeded_snapshots", "2")]
+    );
+
+    deposit_at(&mut deps, "addr", 20, 250);
+
+    // the snapshots are only seeded once
+    let res = migrate(deps.as_mut(), mock_env_at_height(300), MigrateMsg {}).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "migrate"), attr("seeded_snapshots", "0")]
+    );
+
+    for (user, height, stake_amount) in [
+        ("addr", 200u64, 0u128),
+        ("addr", 201, 100),
+        ("addr", 251, 120),
+        ("addr", 301, 120),
+        ("holder", 200, 0),
+        ("holder", 201, 50),
+    ] {
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::UserStakeAtHeight {
+                user: user.to_string(),
+                height,
+            },
+        )
+        .unwrap();
+
+        let stake_res: StakeAtHeightResponse = from_binary(&res).unwrap();
+        assert_eq!(stake_res.stake_amount, Uint128::from(stake_amount));
+    }
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::TotalStakeAtHeight { height: 301 },
+    )
+    .unwrap();
+
+    let stake_res: StakeAtHeightResponse = from_binary(&res).unwrap();
+    assert_eq!(stake_res.stake_amount, Uint128::from(170u128));
+}
@@ -1,9 +1,30 @@
-pub mod claim_test;
-pub mod deposit_test;
-pub mod fund_cw20_test;
-pub mod fund_denom_test;
-pub mod instantiate_test;
-pub mod mock_querier;
-pub mod test_utils;
-pub mod update_config_test;
-pub mod withdraw_test;
+pub mod add_reward_asset_test;
+pub mod all_users_test;
+pub mod approval_test;
+pub mod claim_swap_test;
+pub mod claim_test;
+pub mod compound_test;
+pub mod cw4_test;
+pub mod deposit_test;
+pub mod emergency_withdraw_test;
+pub mod fund_cw20_test;
+pub mod fund_denom_test;
+pub mod fund_stream_test;
+pub mod funder_test;
+pub mod handover_test;
+pub mod hook_test;
+pub mod instantiate_test;
+pub mod lock_test;
+pub mod migration_test;
+pub mod mock_querier;
+pub mod native_stake_test;
+pub mod pause_test;
+pub mod permissionless_fund_test;
+pub mod receipt_token_test;
+pub mod snapshot_test;
+pub mod solvency_test;
+pub mod tax_test;
+pub mod test_utils;
+pub mod unbond_test;
+pub mod update_config_test;
+pub mod withdraw_test;
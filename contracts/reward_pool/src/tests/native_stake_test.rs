@@ -0,0 +1,202 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{
+    attr, coin, from_binary, to_binary, BankMsg, Coin, CosmosMsg, Decimal, OwnedDeps, SubMsg,
+    Uint128,
+};
+
+use cw20::Cw20ReceiveMsg;
+use reward_pool::reward_pool::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, ForfeitMode, InstantiateMsg, QueryMsg,
+    UserInfoResponse,
+};
+use terraswap::asset::AssetInfo;
+
+use crate::{
+    contract::{execute, instantiate, query},
+    error::ContractError,
+    tests::{
+        mock_querier::{mock_dependencies, WasmMockQuerier},
+        test_utils::lock_tiers,
+    },
+};
+
+fn instantiate_native_pool(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    staking_denom: &str,
+) {
+    let msg = InstantiateMsg {
+        governance: String::from("governance"),
+        guardian: None,
+        funders: vec![String::from("funder")],
+        staking_asset_info: AssetInfo::NativeToken {
+            denom: staking_denom.to_string(),
+        },
+        reward_asset_infos: vec![AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        }],
+        lock_tiers: lock_tiers(),
+        unbonding_period: 0,
+        forfeit_mode: ForfeitMode::Redistribute,
+        permissionless_funding: false,
+        min_fund_amounts: vec![],
+        cw4_enabled: false,
+        tokens_per_weight: Uint128::from(1u128),
+        receipt_token: None,
+    };
+
+    let info = mock_info("policy", &[]);
+
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+#[test]
+fn return_native_staking_asset_in_config() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_native_pool(&mut deps, "uluna");
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        config_res.staking_asset_info,
+        AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        }
+    );
+}
+
+#[test]
+fn deposit_native_stake() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_native_pool(&mut deps, "uluna");
+
+    let msg = ExecuteMsg::Deposit {
+        recipient: None,
+        lock_duration: None,
+    };
+
+    let info = mock_info("addr", &[coin(100, "uluna")]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "deposit"),
+            attr("depositor", "addr"),
+            attr("beneficiary", "addr"),
+            attr("amount", "100"),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UserInfo {
+            user: "addr".to_string(),
+        },
+    )
+    .unwrap();
+
+    let user_info_res: UserInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(user_info_res.stake_amount, Uint128::from(100u128));
+}
+
+#[test]
+fn fails_to_deposit_invalid_denom() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_native_pool(&mut deps, "uluna");
+
+    let msg = ExecuteMsg::Deposit {
+        recipient: None,
+        lock_duration: None,
+    };
+
+    let info = mock_info("addr", &[coin(100, "uusd")]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InvalidStakingToken {
+            token: "uusd".to_string()
+        }
+    );
+
+    let info = mock_info("addr", &[coin(100, "uluna"), coin(100, "uusd")]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::InvalidDenomReceived { received: 2 });
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::InvalidDenomReceived { received: 0 });
+}
+
+#[test]
+fn fails_to_deposit_cw20_into_native_pool() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_native_pool(&mut deps, "uluna");
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        msg: to_binary(&Cw20HookMsg::Deposit {
+            recipient: None,
+            lock_duration: None,
+        })
+        .unwrap(),
+        amount: Uint128::from(100u128),
+    });
+
+    let info = mock_info("staking_token", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InvalidStakingToken {
+            token: "staking_token".to_string()
+        }
+    );
+}
+
+#[test]
+fn withdraw_native_stake_with_tax() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_native_pool(&mut deps, "ukrw");
+
+    let tax_rate = Decimal::percent(1);
+    deps.querier
+        .with_tax(tax_rate, &[(&"ukrw".to_string(), &Uint128::from(1000u128))]);
+
+    let msg = ExecuteMsg::Deposit {
+        recipient: None,
+        lock_duration: None,
+    };
+
+    let info = mock_info("addr", &[coin(1000, "ukrw")]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: Uint128::from(1000u128),
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("addr"),
+            amount: vec![Coin {
+                denom: "ukrw".to_string(),
+                amount: Uint128::from(990u128),
+            }]
+        }))]
+    );
+}
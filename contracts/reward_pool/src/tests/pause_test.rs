@@ -0,0 +1,245 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{from_binary, to_binary, Coin, OwnedDeps, Uint128};
+
+use cw20::Cw20ReceiveMsg;
+use reward_pool::reward_pool::{ConfigResponse, Cw20HookMsg, ExecuteMsg, PauseFlags, QueryMsg};
+use terraswap::asset::AssetInfo;
+
+use crate::{
+    contract::{execute, query},
+    error::ContractError,
+    tests::{
+        mock_querier::{mock_dependencies, WasmMockQuerier},
+        test_utils::{deposit, instantiate_reward_pool},
+    },
+};
+
+fn instantiate_with_guardian(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(deps, reward_asset_info).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        guardian: Some(String::from("guardian")),
+        unbonding_period: None,
+        forfeit_mode: None,
+        permissionless_funding: None,
+        min_fund_amounts: None,
+        cw4_enabled: None,
+        tokens_per_weight: None,
+    };
+
+    let info = mock_info("governance", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+fn set_paused(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    sender: &str,
+    paused: Option<bool>,
+) -> Result<(), ContractError> {
+    let msg = ExecuteMsg::SetPaused {
+        deposit: paused,
+        fund: paused,
+        claim: paused,
+        withdraw: paused,
+    };
+
+    let info = mock_info(sender, &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg)?;
+
+    Ok(())
+}
+
+fn query_paused(deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> PauseFlags {
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+    config_res.paused
+}
+
+#[test]
+fn fails_if_caller_is_not_governance_or_guardian() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_with_guardian(&mut deps);
+
+    let res = set_paused(&mut deps, "addr", Some(true)).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+}
+
+#[test]
+fn guardian_can_only_pause() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_with_guardian(&mut deps);
+
+    set_paused(&mut deps, "guardian", Some(true)).unwrap();
+
+    assert_eq!(
+        query_paused(&deps),
+        PauseFlags {
+            deposit: true,
+            fund: true,
+            claim: true,
+            withdraw: true,
+        }
+    );
+
+    let res = set_paused(&mut deps, "guardian", Some(false)).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+}
+
+#[test]
+fn governance_can_pause_and_unpause() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_with_guardian(&mut deps);
+
+    let msg = ExecuteMsg::SetPaused {
+        deposit: Some(true),
+        fund: None,
+        claim: None,
+        withdraw: Some(true),
+    };
+
+    let info = mock_info("governance", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        query_paused(&deps),
+        PauseFlags {
+            deposit: true,
+            fund: false,
+            claim: false,
+            withdraw: true,
+        }
+    );
+
+    set_paused(&mut deps, "governance", Some(false)).unwrap();
+
+    assert_eq!(query_paused(&deps), PauseFlags::default());
+}
+
+#[test]
+fn fails_if_action_is_paused() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_with_guardian(&mut deps);
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    set_paused(&mut deps, "guardian", Some(true)).unwrap();
+
+    let cases = vec![
+        (
+            "staking_token",
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "addr".to_string(),
+                msg: to_binary(&Cw20HookMsg::Deposit {
+                    recipient: None,
+                    lock_duration: None,
+                })
+                .unwrap(),
+                amount: Uint128::from(100u128),
+            }),
+            vec![],
+            "deposit",
+        ),
+        (
+            "funder",
+            ExecuteMsg::Fund {},
+            vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(100u128),
+            }],
+            "fund",
+        ),
+        (
+            "addr",
+            ExecuteMsg::Claim {
+                recipient: None,
+                swap: None,
+            },
+            vec![],
+            "claim",
+        ),
+        (
+            "addr",
+            ExecuteMsg::Withdraw {
+                amount: Uint128::from(100u128),
+                recipient: None,
+            },
+            vec![],
+            "withdraw",
+        ),
+        ("addr", ExecuteMsg::ClaimUnbonded {}, vec![], "withdraw"),
+        ("addr", ExecuteMsg::EmergencyWithdraw {}, vec![], "withdraw"),
+    ];
+
+    for (sender, msg, funds, action) in cases {
+        let info = mock_info(sender, &funds);
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::Paused {
+                action: action.to_string(),
+            }
+        );
+    }
+}
+
+#[test]
+fn pause_actions_independently() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_with_guardian(&mut deps);
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let msg = ExecuteMsg::SetPaused {
+        deposit: Some(true),
+        fund: None,
+        claim: None,
+        withdraw: None,
+    };
+
+    let info = mock_info("guardian", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        msg: to_binary(&Cw20HookMsg::Deposit {
+            recipient: None,
+            lock_duration: None,
+        })
+        .unwrap(),
+        amount: Uint128::from(100u128),
+    });
+
+    let info = mock_info("staking_token", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::Paused {
+            action: "deposit".to_string(),
+        }
+    );
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: Uint128::from(100u128),
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
@@ -0,0 +1,329 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{attr, from_binary, Coin, OwnedDeps, Uint128};
+
+use reward_pool::reward_pool::{
+    ConfigResponse, ExecuteMsg, FundHistoryResponse, FundRecord, FundersResponse, QueryMsg,
+};
+use terraswap::asset::{Asset, AssetInfo};
+
+use crate::{
+    contract::{execute, query},
+    error::ContractError,
+    tests::{
+        mock_querier::{mock_dependencies, WasmMockQuerier},
+        test_utils::{fund, instantiate_reward_pool, uusd},
+    },
+};
+
+fn enable_permissionless_funding(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    min_fund_amount: u128,
+) {
+    let msg = ExecuteMsg::UpdateConfig {
+        guardian: None,
+        unbonding_period: None,
+        forfeit_mode: None,
+        permissionless_funding: Some(true),
+        min_fund_amounts: Some(vec![Asset {
+            info: uusd(),
+            amount: Uint128::from(min_fund_amount),
+        }]),
+        cw4_enabled: None,
+        tokens_per_weight: None,
+    };
+
+    let info = mock_info("governance", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+fn query_fund_history(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Vec<FundRecord> {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::FundHistory { start_after, limit },
+    )
+    .unwrap();
+
+    let history_res: FundHistoryResponse = from_binary(&res).unwrap();
+    history_res.records
+}
+
+#[test]
+fn fails_if_not_permissionless() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    let res = fund(&mut deps, "partner", uusd(), 1000).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+}
+
+#[test]
+fn anyone_can_fund_if_permissionless() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+    enable_permissionless_funding(&mut deps, 100);
+
+    let info = mock_info(
+        "partner",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100u128),
+        }],
+    );
+
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "fund"),
+            attr("funder", "partner"),
+            attr("amount", "100"),
+            attr("tax_amount", "0"),
+        ]
+    );
+
+    // permissionless funders are not added to the funder set
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Funders {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let funders_res: FundersResponse = from_binary(&res).unwrap();
+    assert_eq!(funders_res.funders.len(), 1);
+    assert_eq!(funders_res.funders[0].funder, "funder");
+}
+
+#[test]
+fn fails_if_amount_is_smaller_than_minimum() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+    enable_permissionless_funding(&mut deps, 100);
+
+    let res = fund(&mut deps, "partner", uusd(), 99).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::FundAmountTooSmall {
+            amount: Uint128::from(99u128),
+            min_amount: Uint128::from(100u128),
+        }
+    );
+
+    // enabled funders are not bound to the minimum
+    fund(&mut deps, "funder", uusd(), 1).unwrap();
+}
+
+#[test]
+fn fails_to_enable_without_min_fund_amount() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    let info = mock_info("governance", &[]);
+
+    for min_fund_amounts in [
+        None,
+        Some(vec![]),
+        Some(vec![Asset {
+            info: uusd(),
+            amount: Uint128::zero(),
+        }]),
+    ] {
+        let msg = ExecuteMsg::UpdateConfig {
+            guardian: None,
+            unbonding_period: None,
+            forfeit_mode: None,
+            permissionless_funding: Some(true),
+            min_fund_amounts,
+            cw4_enabled: None,
+            tokens_per_weight: None,
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::MinFundAmountRequired {
+                asset_info: "uusd".to_string()
+            }
+        );
+    }
+
+    let msg = ExecuteMsg::UpdateConfig {
+        guardian: None,
+        unbonding_period: None,
+        forfeit_mode: None,
+        permissionless_funding: Some(true),
+        min_fund_amounts: Some(vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            amount: Uint128::from(100u128),
+        }]),
+        cw4_enabled: None,
+        tokens_per_weight: None,
+    };
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::UnsupportedRewardAsset {
+            asset_info: "ukrw".to_string()
+        }
+    );
+}
+
+#[test]
+fn fails_to_fund_reward_asset_without_min_fund_amount() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+    enable_permissionless_funding(&mut deps, 100);
+
+    let ukrw = AssetInfo::NativeToken {
+        denom: "ukrw".to_string(),
+    };
+    let msg = ExecuteMsg::AddRewardAsset {
+        asset_info: ukrw.clone(),
+    };
+
+    let info = mock_info("governance", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        config_res.min_fund_amounts,
+        vec![Asset {
+            info: uusd(),
+            amount: Uint128::from(100u128),
+        }]
+    );
+
+    let res = fund(&mut deps, "partner", ukrw.clone(), 1000).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::MinFundAmountRequired {
+            asset_info: "ukrw".to_string()
+        }
+    );
+
+    fund(&mut deps, "partner", uusd(), 100).unwrap();
+    fund(&mut deps, "funder", ukrw, 1).unwrap();
+}
+
+#[test]
+fn fails_to_fund_stream_if_not_funder() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+    enable_permissionless_funding(&mut deps, 100);
+
+    let start_time = mock_env().block.time.seconds();
+    let msg = ExecuteMsg::FundStream {
+        start_time,
+        end_time: start_time + 100,
+    };
+
+    let info = mock_info(
+        "partner",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100u128),
+        }],
+    );
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100u128),
+        }],
+    );
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+#[test]
+fn record_fund_history() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+    enable_permissionless_funding(&mut deps, 100);
+
+    fund(&mut deps, "funder", uusd(), 1000).unwrap();
+    fund(&mut deps, "partner", uusd(), 200).unwrap();
+
+    let start_time = mock_env().block.time.seconds() + 10;
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(300u128),
+        }],
+    );
+    let msg = ExecuteMsg::FundStream {
+        start_time,
+        end_time: start_time + 100,
+    };
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let now = mock_env().block.time.seconds();
+    assert_eq!(
+        query_fund_history(&deps, None, None),
+        vec![
+            FundRecord {
+                id: 0,
+                funder: String::from("funder"),
+                asset: Asset {
+                    info: uusd(),
+                    amount: Uint128::from(1000u128),
+                },
+                time: now,
+                start_time: None,
+                end_time: None,
+            },
+            FundRecord {
+                id: 1,
+                funder: String::from("partner"),
+                asset: Asset {
+                    info: uusd(),
+                    amount: Uint128::from(200u128),
+                },
+                time: now,
+                start_time: None,
+                end_time: None,
+            },
+            FundRecord {
+                id: 2,
+                funder: String::from("funder"),
+                asset: Asset {
+                    info: uusd(),
+                    amount: Uint128::from(300u128),
+                },
+                time: now,
+                start_time: Some(start_time),
+                end_time: Some(start_time + 100),
+            },
+        ]
+    );
+
+    let records = query_fund_history(&deps, Some(0), Some(1));
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].id, 1);
+}
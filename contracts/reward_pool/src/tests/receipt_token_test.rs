@@ -0,0 +1,756 @@
+use cosmwasm_std::testing::{
+    mock_dependencies as mock_token_dependencies, mock_env, mock_info, MockApi, MockQuerier,
+    MockStorage, MOCK_CONTRACT_ADDR,
+};
+use cosmwasm_std::{
+    attr, coin, from_binary, to_binary, Addr, Binary, ContractResult, CosmosMsg, Env, Event,
+    OwnedDeps, Reply, SubMsg, SubMsgExecutionResponse, Uint128, WasmMsg,
+};
+
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse, TokenInfoResponse};
+use reward_pool::reward_pool::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, ForfeitMode, InstantiateMsg, QueryMsg,
+    ReceiptTokenInfo, ReceiptTokenInstantiateMsg, ReceiptTransferHookMsg, UserInfoResponse,
+};
+use terraswap::asset::AssetInfo;
+
+use crate::{
+    contract::{execute, instantiate, query, reply},
+    error::ContractError,
+    execute::RECEIPT_TOKEN_REPLY_ID,
+    tests::{
+        mock_querier::{mock_dependencies, WasmMockQuerier},
+        test_utils::{deposit, instantiate_reward_pool, lock_tiers, uusd, MONTH},
+    },
+};
+
+fn register_receipt_token(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let msg = Reply {
+        id: RECEIPT_TOKEN_REPLY_ID,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![Event::new("instantiate_contract")
+                .add_attribute("code_id", "7")
+                .add_attribute("contract_address", "receipt_token")],
+            data: None,
+        }),
+    };
+
+    reply(deps.as_mut(), mock_env(), msg).unwrap();
+}
+
+fn receipt_msg(msg: Cw20ExecuteMsg) -> CosmosMsg {
+    CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "receipt_token".to_string(),
+        msg: to_binary(&msg).unwrap(),
+        funds: vec![],
+    })
+}
+
+fn transfer_receipts(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    sender: &str,
+    recipient: &str,
+    amount: u128,
+) -> Result<(), ContractError> {
+    let msg = ExecuteMsg::ReceiptTransferHook(ReceiptTransferHookMsg {
+        sender: sender.to_string(),
+        recipient: recipient.to_string(),
+        amount: Uint128::from(amount),
+    });
+
+    let info = mock_info("receipt_token", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg)?;
+
+    Ok(())
+}
+
+fn send_receipts(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    sender: &str,
+    amount: u128,
+    msg: Cw20HookMsg,
+) -> Result<Vec<SubMsg>, ContractError> {
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: sender.to_string(),
+        msg: to_binary(&msg).unwrap(),
+        amount: Uint128::from(amount),
+    });
+
+    let info = mock_info("receipt_token", &[]);
+
+    Ok(execute(deps.as_mut(), mock_env(), info, msg)?.messages)
+}
+
+fn query_user_info(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    user: &str,
+) -> UserInfoResponse {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UserInfo {
+            user: user.to_string(),
+        },
+    )
+    .unwrap();
+
+    from_binary(&res).unwrap()
+}
+
+#[test]
+fn instantiate_receipt_token() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        governance: String::from("governance"),
+        guardian: None,
+        funders: vec![String::from("funder")],
+        staking_asset_info: AssetInfo::Token {
+            contract_addr: String::from("staking_token"),
+        },
+        reward_asset_infos: vec![AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        }],
+        lock_tiers: lock_tiers(),
+        unbonding_period: 0,
+        forfeit_mode: ForfeitMode::Redistribute,
+        permissionless_funding: false,
+        min_fund_amounts: vec![],
+        cw4_enabled: false,
+        tokens_per_weight: Uint128::from(1u128),
+        receipt_token: Some(ReceiptTokenInfo {
+            code_id: 7,
+            name: "Staked Token".to_string(),
+            symbol: "sTKN".to_string(),
+            decimals: 6,
+        }),
+    };
+
+    let info = mock_info("policy", &[]);
+
+    let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            WasmMsg::Instantiate {
+                admin: None,
+                code_id: 7,
+                msg: to_binary(&ReceiptTokenInstantiateMsg {
+                    name: "Staked Token".to_string(),
+                    symbol: "sTKN".to_string(),
+                    decimals: 6,
+                    initial_balances: vec![],
+                    mint: Some(MinterResponse {
+                        minter: MOCK_CONTRACT_ADDR.to_string(),
+                        cap: None,
+                    }),
+                })
+                .unwrap(),
+                funds: vec![],
+                label: "Staked Token".to_string(),
+            },
+            RECEIPT_TOKEN_REPLY_ID,
+        )]
+    );
+
+    register_receipt_token(&mut deps);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config_res.receipt_token, Some("receipt_token".to_string()));
+}
+
+#[test]
+fn fails_to_register_without_token_address() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    let msg = Reply {
+        id: RECEIPT_TOKEN_REPLY_ID,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![Event::new("instantiate_contract").add_attribute("code_id", "7")],
+            data: None,
+        }),
+    };
+
+    let res = reply(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert_eq!(res, ContractError::ReceiptTokenNotFound {});
+
+    let msg = Reply {
+        id: 99,
+        result: ContractResult::Err("failed".to_string()),
+    };
+
+    let res = reply(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert_eq!(res, ContractError::UnknownReplyId { id: 99 });
+}
+
+#[test]
+fn mint_and_burn_receipts() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+    register_receipt_token(&mut deps);
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        msg: to_binary(&Cw20HookMsg::Deposit {
+            recipient: None,
+            lock_duration: None,
+        })
+        .unwrap(),
+        amount: Uint128::from(100u128),
+    });
+
+    let info = mock_info("staking_token", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(receipt_msg(Cw20ExecuteMsg::Mint {
+            recipient: "addr".to_string(),
+            amount: Uint128::from(100u128),
+        }))]
+    );
+
+    let res = send_receipts(
+        &mut deps,
+        "addr",
+        30,
+        Cw20HookMsg::Withdraw { recipient: None },
+    )
+    .unwrap();
+    assert_eq!(
+        res[1..].to_vec(),
+        vec![SubMsg::new(receipt_msg(Cw20ExecuteMsg::Burn {
+            amount: Uint128::from(30u128),
+        }))]
+    );
+
+    let res = send_receipts(&mut deps, "addr", 70, Cw20HookMsg::EmergencyWithdraw {}).unwrap();
+    assert_eq!(
+        res[1..].to_vec(),
+        vec![SubMsg::new(receipt_msg(Cw20ExecuteMsg::Burn {
+            amount: Uint128::from(70u128),
+        }))]
+    );
+}
+
+#[test]
+fn fails_to_withdraw_without_receipts() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+    register_receipt_token(&mut deps);
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let info = mock_info("addr", &[]);
+
+    for msg in [
+        ExecuteMsg::Withdraw {
+            amount: Uint128::from(30u128),
+            recipient: None,
+        },
+        ExecuteMsg::EmergencyWithdraw {},
+    ] {
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(res, ContractError::ReceiptsRequired {});
+    }
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        msg: to_binary(&Cw20HookMsg::Withdraw { recipient: None }).unwrap(),
+        amount: Uint128::from(30u128),
+    });
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking_token", &[]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let res = send_receipts(&mut deps, "addr", 30, Cw20HookMsg::EmergencyWithdraw {}).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::ReceiptAmountMismatch {
+            received: Uint128::from(30u128),
+            stake: Uint128::from(100u128),
+        }
+    );
+}
+
+#[test]
+fn fails_to_approve_withdrawals() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+    register_receipt_token(&mut deps);
+
+    let info = mock_info("addr", &[]);
+
+    let msg = ExecuteMsg::Approve {
+        operator: "keeper".to_string(),
+        can_withdraw: true,
+        expires: None,
+    };
+
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(res, ContractError::ReceiptsRequired {});
+
+    // operators can still claim for the owner
+    let msg = ExecuteMsg::Approve {
+        operator: "keeper".to_string(),
+        can_withdraw: false,
+        expires: None,
+    };
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+#[test]
+fn rewards_follow_receipt_holder() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+    register_receipt_token(&mut deps);
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let info = mock_info("funder", &[coin(100, "uusd")]);
+
+    execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::Fund {}).unwrap();
+
+    let msg = ExecuteMsg::ReceiptTransferHook(ReceiptTransferHookMsg {
+        sender: "addr".to_string(),
+        recipient: "holder".to_string(),
+        amount: Uint128::from(40u128),
+    });
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("receipt_token", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "receipt_transfer"),
+            attr("sender", "addr"),
+            attr("recipient", "holder"),
+            attr("amount", "40"),
+        ]
+    );
+
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+
+    let addr_info = query_user_info(&deps, "addr");
+    assert_eq!(addr_info.stake_amount, Uint128::from(60u128));
+    assert_eq!(addr_info.pending_rewards[0].amount, Uint128::from(160u128));
+
+    let holder_info = query_user_info(&deps, "holder");
+    assert_eq!(holder_info.stake_amount, Uint128::from(40u128));
+    assert_eq!(holder_info.pending_rewards[0].amount, Uint128::from(40u128));
+}
+
+#[test]
+fn compound_rewards_on_receipt_transfer() {
+    let mut deps = mock_dependencies(&[]);
+
+    let staking_token = AssetInfo::Token {
+        contract_addr: "staking_token".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, staking_token).unwrap();
+    register_receipt_token(&mut deps);
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let info = mock_info("addr", &[]);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::SetAutoCompound { enabled: true },
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "funder".to_string(),
+        msg: to_binary(&Cw20HookMsg::Fund {}).unwrap(),
+        amount: Uint128::from(100u128),
+    });
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking_token", &[]),
+        msg,
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::ReceiptTransferHook(ReceiptTransferHookMsg {
+        sender: "addr".to_string(),
+        recipient: "holder".to_string(),
+        amount: Uint128::from(40u128),
+    });
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("receipt_token", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(receipt_msg(Cw20ExecuteMsg::Mint {
+            recipient: "addr".to_string(),
+            amount: Uint128::from(100u128),
+        }))]
+    );
+
+    let addr_info = query_user_info(&deps, "addr");
+    assert_eq!(addr_info.stake_amount, Uint128::from(160u128));
+    assert!(addr_info.pending_rewards[0].amount.is_zero());
+    assert_eq!(
+        query_user_info(&deps, "holder").stake_amount,
+        Uint128::from(40u128)
+    );
+}
+
+#[test]
+fn fails_if_caller_is_not_receipt_token() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let msg = ExecuteMsg::ReceiptTransferHook(ReceiptTransferHookMsg {
+        sender: "addr".to_string(),
+        recipient: "holder".to_string(),
+        amount: Uint128::from(40u128),
+    });
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("receipt_token", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    register_receipt_token(&mut deps);
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("addr", &[]), msg).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+}
+
+#[test]
+fn fails_to_transfer_locked_stake() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+    register_receipt_token(&mut deps);
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        msg: to_binary(&Cw20HookMsg::Deposit {
+            recipient: None,
+            lock_duration: Some(MONTH),
+        })
+        .unwrap(),
+        amount: Uint128::from(50u128),
+    });
+
+    let info = mock_info("staking_token", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = transfer_receipts(&mut deps, "addr", "holder", 120).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InsufficientStake {
+            requested: Uint128::from(120u128),
+            available: Uint128::from(100u128),
+        }
+    );
+
+    transfer_receipts(&mut deps, "addr", "holder", 100).unwrap();
+    assert_eq!(
+        query_user_info(&deps, "holder").stake_amount,
+        Uint128::from(100u128)
+    );
+}
+
+fn token_env() -> Env {
+    let mut env = mock_env();
+    env.contract.address = Addr::unchecked("receipt_token");
+    env
+}
+
+fn instantiate_token(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>) {
+    let msg = to_binary(&ReceiptTokenInstantiateMsg {
+        name: "Staked Token".to_string(),
+        symbol: "sTKN".to_string(),
+        decimals: 6,
+        initial_balances: vec![],
+        mint: Some(MinterResponse {
+            minter: MOCK_CONTRACT_ADDR.to_string(),
+            cap: None,
+        }),
+    })
+    .unwrap();
+
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+
+    receipt_token::contract::instantiate(
+        deps.as_mut(),
+        token_env(),
+        info,
+        from_binary(&msg).unwrap(),
+    )
+    .unwrap();
+}
+
+/// Executes the messages between the pool and the receipt token in order,
+/// the messages to other contracts are dropped
+fn route(
+    pool: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    token: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    messages: Vec<SubMsg>,
+) -> Result<(), String> {
+    for sub_msg in messages {
+        let (contract_addr, msg) = match sub_msg.msg {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr, msg, ..
+            }) => (contract_addr, msg),
+            _ => continue,
+        };
+
+        let messages = if contract_addr == "receipt_token" {
+            let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+            receipt_token::contract::execute(
+                token.as_mut(),
+                token_env(),
+                info,
+                from_binary(&msg).unwrap(),
+            )
+            .map_err(|err| err.to_string())?
+            .messages
+        } else if contract_addr == MOCK_CONTRACT_ADDR {
+            let info = mock_info("receipt_token", &[]);
+            execute(pool.as_mut(), mock_env(), info, from_binary(&msg).unwrap())
+                .map_err(|err| err.to_string())?
+                .messages
+        } else {
+            continue;
+        };
+        route(pool, token, messages)?;
+    }
+
+    Ok(())
+}
+
+fn execute_token(
+    pool: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    token: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    sender: &str,
+    msg: Cw20ExecuteMsg,
+) -> Result<(), String> {
+    let res = receipt_token::contract::execute(
+        token.as_mut(),
+        token_env(),
+        mock_info(sender, &[]),
+        from_binary(&to_binary(&msg).unwrap()).unwrap(),
+    )
+    .map_err(|err| err.to_string())?;
+
+    route(pool, token, res.messages)
+}
+
+fn query_token<T: serde::de::DeserializeOwned>(
+    token: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    msg: &str,
+) -> T {
+    let res = receipt_token::contract::query(
+        token.as_ref(),
+        token_env(),
+        from_binary(&Binary::from(msg.as_bytes())).unwrap(),
+    )
+    .unwrap();
+
+    from_binary(&res).unwrap()
+}
+
+fn query_receipt_balance(
+    token: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    user: &str,
+) -> Uint128 {
+    let res: BalanceResponse =
+        query_token(token, &format!(r#"{{"balance":{{"address":"{}"}}}}"#, user));
+    res.balance
+}
+
+#[test]
+fn withdraw_by_sending_receipts() {
+    let mut pool = mock_dependencies(&[]);
+    let mut token = mock_token_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut pool, reward_asset_info).unwrap();
+    register_receipt_token(&mut pool);
+    instantiate_token(&mut token);
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        msg: to_binary(&Cw20HookMsg::Deposit {
+            recipient: None,
+            lock_duration: None,
+        })
+        .unwrap(),
+        amount: Uint128::from(100u128),
+    });
+
+    let res = execute(
+        pool.as_mut(),
+        mock_env(),
+        mock_info("staking_token", &[]),
+        msg,
+    )
+    .unwrap();
+    route(&mut pool, &mut token, res.messages).unwrap();
+    assert_eq!(
+        query_receipt_balance(&token, "addr"),
+        Uint128::from(100u128)
+    );
+
+    let msg = Cw20ExecuteMsg::Transfer {
+        recipient: "holder".to_string(),
+        amount: Uint128::from(40u128),
+    };
+
+    execute_token(&mut pool, &mut token, "addr", msg).unwrap();
+    assert_eq!(
+        query_user_info(&pool, "holder").stake_amount,
+        Uint128::from(40u128)
+    );
+
+    let msg = Cw20ExecuteMsg::Send {
+        contract: MOCK_CONTRACT_ADDR.to_string(),
+        amount: Uint128::from(40u128),
+        msg: to_binary(&Cw20HookMsg::Withdraw { recipient: None }).unwrap(),
+    };
+
+    execute_token(&mut pool, &mut token, "holder", msg).unwrap();
+    assert_eq!(
+        query_user_info(&pool, "holder").stake_amount,
+        Uint128::zero()
+    );
+    assert_eq!(query_receipt_balance(&token, "holder"), Uint128::zero());
+    assert_eq!(
+        query_receipt_balance(&token, MOCK_CONTRACT_ADDR),
+        Uint128::zero()
+    );
+
+    let msg = Cw20ExecuteMsg::Send {
+        contract: MOCK_CONTRACT_ADDR.to_string(),
+        amount: Uint128::from(60u128),
+        msg: to_binary(&Cw20HookMsg::EmergencyWithdraw {}).unwrap(),
+    };
+
+    execute_token(&mut pool, &mut token, "addr", msg).unwrap();
+    assert_eq!(query_user_info(&pool, "addr").stake_amount, Uint128::zero());
+
+    let token_info: TokenInfoResponse = query_token(&token, r#"{"token_info":{}}"#);
+    assert_eq!(token_info.total_supply, Uint128::zero());
+}
+
+#[test]
+fn fails_to_send_locked_receipts_for_withdrawal() {
+    let mut pool = mock_dependencies(&[]);
+    let mut token = mock_token_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut pool, reward_asset_info).unwrap();
+    register_receipt_token(&mut pool);
+    instantiate_token(&mut token);
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        msg: to_binary(&Cw20HookMsg::Deposit {
+            recipient: None,
+            lock_duration: Some(MONTH),
+        })
+        .unwrap(),
+        amount: Uint128::from(100u128),
+    });
+
+    let res = execute(
+        pool.as_mut(),
+        mock_env(),
+        mock_info("staking_token", &[]),
+        msg,
+    )
+    .unwrap();
+    route(&mut pool, &mut token, res.messages).unwrap();
+
+    let msg = Cw20ExecuteMsg::Send {
+        contract: MOCK_CONTRACT_ADDR.to_string(),
+        amount: Uint128::from(40u128),
+        msg: to_binary(&Cw20HookMsg::Withdraw { recipient: None }).unwrap(),
+    };
+
+    let res = execute_token(&mut pool, &mut token, "addr", msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InsufficientStake {
+            requested: Uint128::from(40u128),
+            available: Uint128::zero(),
+        }
+        .to_string()
+    );
+}
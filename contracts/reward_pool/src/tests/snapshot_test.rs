@@ -0,0 +1,161 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{coin, from_binary, Order, OwnedDeps, Storage, Uint128};
+
+use reward_pool::reward_pool::{ExecuteMsg, QueryMsg, StakeAtHeightResponse};
+use terraswap::asset::AssetInfo;
+
+use crate::{
+    contract::{execute, query},
+    tests::{
+        mock_querier::{mock_dependencies, WasmMockQuerier},
+        test_utils::{deposit_at, instantiate_reward_pool, mock_env_at_height},
+    },
+};
+
+fn query_user_stake(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    user: &str,
+    height: u64,
+) -> Uint128 {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UserStakeAtHeight {
+            user: user.to_string(),
+            height,
+        },
+    )
+    .unwrap();
+
+    let stake_res: StakeAtHeightResponse = from_binary(&res).unwrap();
+    stake_res.stake_amount
+}
+
+/// Number of stake changes recorded in the changelog of the user stakes
+fn count_stake_changes(deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> usize {
+    let namespace = b"user_stakes__changelog";
+    let mut prefix = (namespace.len() as u16).to_be_bytes().to_vec();
+    prefix.extend_from_slice(namespace);
+    deps.storage
+        .range(None, None, Order::Ascending)
+        .filter(|(key, _)| key.starts_with(&prefix))
+        .count()
+}
+
+fn query_total_stake(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    height: u64,
+) -> Uint128 {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::TotalStakeAtHeight { height },
+    )
+    .unwrap();
+
+    let stake_res: StakeAtHeightResponse = from_binary(&res).unwrap();
+    stake_res.stake_amount
+}
+
+#[test]
+fn query_stake_at_height() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    deposit_at(&mut deps, "addr", 100, 100);
+    deposit_at(&mut deps, "addr", 50, 200);
+    deposit_at(&mut deps, "staker", 200, 200);
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: Uint128::from(30u128),
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    execute(deps.as_mut(), mock_env_at_height(300), info, msg).unwrap();
+
+    // changes are included from the next block
+    for (height, addr_stake, staker_stake, total_stake) in [
+        (100u64, 0u128, 0u128, 0u128),
+        (101, 100, 0, 100),
+        (200, 100, 0, 100),
+        (201, 150, 200, 350),
+        (300, 150, 200, 350),
+        (301, 120, 200, 320),
+    ] {
+        assert_eq!(
+            query_user_stake(&deps, "addr", height),
+            Uint128::from(addr_stake)
+        );
+        assert_eq!(
+            query_user_stake(&deps, "staker", height),
+            Uint128::from(staker_stake)
+        );
+        assert_eq!(query_total_stake(&deps, height), Uint128::from(total_stake));
+    }
+}
+
+#[test]
+fn snapshot_emergency_withdraw() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    deposit_at(&mut deps, "addr", 100, 100);
+
+    let info = mock_info("addr", &[]);
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_height(200),
+        info,
+        ExecuteMsg::EmergencyWithdraw {},
+    )
+    .unwrap();
+
+    assert_eq!(query_user_stake(&deps, "addr", 200), Uint128::from(100u128));
+    assert_eq!(query_user_stake(&deps, "addr", 201), Uint128::zero());
+    assert_eq!(query_total_stake(&deps, 201), Uint128::zero());
+}
+
+#[test]
+fn skip_snapshot_on_claim_without_stake_change() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    deposit_at(&mut deps, "addr", 100, 100);
+
+    let info = mock_info("funder", &[coin(100, "uusd")]);
+
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+
+    let stake_changes = count_stake_changes(&deps);
+
+    let msg = ExecuteMsg::Claim {
+        recipient: None,
+        swap: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    execute(deps.as_mut(), mock_env_at_height(200), info, msg).unwrap();
+
+    assert_eq!(count_stake_changes(&deps), stake_changes);
+    assert_eq!(query_user_stake(&deps, "addr", 201), Uint128::from(100u128));
+    assert_eq!(query_total_stake(&deps, 201), Uint128::from(100u128));
+}
@@ -0,0 +1,173 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, Coin, Decimal256, Uint128};
+
+use reward_pool::reward_pool::{
+    ExecuteMsg, QueryMsg, RewardSolvency, RewardState, SolvencyResponse, State,
+};
+use terraswap::asset::AssetInfo;
+
+use crate::{
+    contract::{execute, query},
+    state::STATE,
+    tests::{
+        mock_querier::mock_dependencies,
+        test_utils::{deposit, instantiate_reward_pool},
+    },
+};
+
+#[test]
+fn carry_over_rounding_remainder() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info).unwrap();
+
+    let total_deposits = Uint128::from(3_000_000_000_000_000_000u128);
+
+    STATE
+        .save(
+            &mut deps.storage,
+            &State {
+                total_deposits,
+                total_weight: total_deposits,
+                total_unbonding: Uint128::zero(),
+                staker_count: 0,
+                rewards: vec![RewardState::default()],
+            },
+        )
+        .unwrap();
+
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(10u128),
+        }],
+    );
+
+    execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::Fund {}).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(
+        State {
+            total_deposits,
+            total_weight: total_deposits,
+            total_unbonding: Uint128::zero(),
+            staker_count: 0,
+            rewards: vec![RewardState {
+                acc_per_share: Decimal256::from_ratio(3u128, 1_000_000_000_000_000_000u128),
+                undistributed_amount: Uint128::from(1u128),
+                distributed_amount: Uint128::from(9u128),
+                claimed_amount: Uint128::zero(),
+                forfeited_amount: Uint128::zero(),
+                tax_reserve: Uint128::zero(),
+            }],
+        },
+        state_res
+    );
+
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(
+        State {
+            total_deposits,
+            total_weight: total_deposits,
+            total_unbonding: Uint128::zero(),
+            staker_count: 0,
+            rewards: vec![RewardState {
+                acc_per_share: Decimal256::from_ratio(6u128, 1_000_000_000_000_000_000u128),
+                undistributed_amount: Uint128::from(2u128),
+                distributed_amount: Uint128::from(18u128),
+                claimed_amount: Uint128::zero(),
+                forfeited_amount: Uint128::zero(),
+                tax_reserve: Uint128::zero(),
+            }],
+        },
+        state_res
+    );
+}
+
+#[test]
+fn query_solvency() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::from(1200u128),
+    }]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(1000u128),
+        }],
+    );
+
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+
+    let start_time = mock_env().block.time.seconds() + 10;
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(500u128),
+        }],
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::FundStream {
+            start_time,
+            end_time: start_time + 100,
+        },
+    )
+    .unwrap();
+
+    let info = mock_info("addr", &[]);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::Claim {
+            recipient: None,
+            swap: None,
+        },
+    )
+    .unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Solvency {}).unwrap();
+
+    let solvency_res: SolvencyResponse = from_binary(&res).unwrap();
+
+    assert_eq!(
+        SolvencyResponse {
+            rewards: vec![RewardSolvency {
+                asset_info: reward_asset_info,
+                balance: Uint128::from(1200u128),
+                owed_amount: Uint128::from(500u128),
+                tax_reserve: Uint128::zero(),
+            }],
+        },
+        solvency_res
+    );
+}
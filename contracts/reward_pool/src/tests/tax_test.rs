@@ -0,0 +1,406 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{
+    attr, coin, from_binary, BankMsg, Coin, CosmosMsg, Decimal, OwnedDeps, SubMsg, Uint128,
+};
+
+use reward_pool::reward_pool::{
+    ClaimableReward, ClaimableRewardsResponse, ExecuteMsg, QueryMsg, RewardSchedulesResponse,
+    SolvencyResponse, State, UserInfoResponse,
+};
+use terraswap::asset::AssetInfo;
+
+use crate::{
+    contract::{execute, query},
+    error::ContractError,
+    tests::{
+        mock_querier::{mock_dependencies, WasmMockQuerier},
+        test_utils::{deposit, instantiate_reward_pool},
+    },
+};
+
+fn query_tax_reserve(deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Uint128 {
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+    state_res.rewards[0].tax_reserve
+}
+
+fn claim(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Vec<SubMsg> {
+    let msg = ExecuteMsg::Claim {
+        recipient: None,
+        swap: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg)
+        .unwrap()
+        .messages
+}
+
+#[test]
+fn reserve_tax_on_fund() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let info = mock_info("funder", &[coin(1010, "uusd")]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "fund"),
+            attr("funder", "funder"),
+            attr("amount", "1010"),
+            attr("tax_amount", "10"),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UserInfo {
+            user: "addr".to_string(),
+        },
+    )
+    .unwrap();
+
+    let user_info_res: UserInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        user_info_res.pending_rewards[0].amount,
+        Uint128::from(1000u128)
+    );
+}
+
+#[test]
+fn reserve_tax_on_fund_stream() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let start_time = mock_env().block.time.seconds();
+    let msg = ExecuteMsg::FundStream {
+        start_time,
+        end_time: start_time + 100,
+    };
+
+    let info = mock_info("funder", &[coin(1010, "uusd")]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::RewardSchedules {}).unwrap();
+
+    let schedules_res: RewardSchedulesResponse = from_binary(&res).unwrap();
+    assert_eq!(schedules_res.schedules[0].amount, Uint128::from(1000u128));
+}
+
+#[test]
+fn do_not_reserve_tax_of_luna() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uluna".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uluna".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let info = mock_info("funder", &[coin(1010, "uluna")]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "fund"),
+            attr("funder", "funder"),
+            attr("amount", "1010"),
+            attr("tax_amount", "0"),
+        ]
+    );
+}
+
+#[test]
+fn pay_tax_of_claimed_rewards() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let info = mock_info("funder", &[coin(1010, "uusd")]);
+
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ClaimableRewards {
+            user: "addr".to_string(),
+        },
+    )
+    .unwrap();
+
+    let claimable_res: ClaimableRewardsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        claimable_res.rewards,
+        vec![ClaimableReward {
+            asset_info: reward_asset_info,
+            amount: Uint128::from(1000u128),
+            tax_amount: Uint128::from(10u128),
+        }]
+    );
+
+    let msg = ExecuteMsg::Claim {
+        recipient: None,
+        swap: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("addr"),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(1000u128),
+            }]
+        }))]
+    );
+}
+
+#[test]
+fn apply_tax_cap_to_claimable_rewards() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(3u128))],
+    );
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let info = mock_info("funder", &[coin(1010, "uusd")]);
+
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ClaimableRewards {
+            user: "addr".to_string(),
+        },
+    )
+    .unwrap();
+
+    let claimable_res: ClaimableRewardsResponse = from_binary(&res).unwrap();
+    assert_eq!(claimable_res.rewards[0].amount, Uint128::from(1000u128));
+    assert_eq!(claimable_res.rewards[0].tax_amount, Uint128::from(3u128));
+}
+
+#[test]
+fn pay_tax_out_of_tax_reserve() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(3u128))],
+    );
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let info = mock_info("funder", &[coin(1010, "uusd")]);
+
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+    assert_eq!(query_tax_reserve(&deps), Uint128::from(10u128));
+
+    claim(&mut deps);
+    assert_eq!(query_tax_reserve(&deps), Uint128::from(7u128));
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Solvency {}).unwrap();
+
+    let solvency_res: SolvencyResponse = from_binary(&res).unwrap();
+    assert_eq!(solvency_res.rewards[0].owed_amount, Uint128::from(7u128));
+    assert_eq!(solvency_res.rewards[0].tax_reserve, Uint128::from(7u128));
+}
+
+#[test]
+fn deduct_tax_if_tax_reserve_is_short() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let info = mock_info("funder", &[coin(1000, "uusd")]);
+
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+    assert_eq!(query_tax_reserve(&deps), Uint128::zero());
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(1000000u128))],
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ClaimableRewards {
+            user: "addr".to_string(),
+        },
+    )
+    .unwrap();
+
+    let claimable_res: ClaimableRewardsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        claimable_res.rewards,
+        vec![ClaimableReward {
+            asset_info: reward_asset_info,
+            amount: Uint128::from(990u128),
+            tax_amount: Uint128::from(10u128),
+        }]
+    );
+
+    assert_eq!(
+        claim(&mut deps),
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("addr"),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::from(990u128),
+            }]
+        }))]
+    );
+}
+
+#[test]
+fn distribute_tax_reserve() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    deps.querier.with_tax(
+        Decimal::percent(1),
+        &[(&"uusd".to_string(), &Uint128::from(3u128))],
+    );
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let info = mock_info("funder", &[coin(1010, "uusd")]);
+
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {}).unwrap();
+
+    claim(&mut deps);
+
+    let msg = ExecuteMsg::DistributeTaxReserve {
+        asset_info: reward_asset_info.clone(),
+        amount: Uint128::from(8u128),
+    };
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let info = mock_info("governance", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InsufficientTaxReserve {
+            requested: Uint128::from(8u128),
+            available: Uint128::from(7u128),
+        }
+    );
+
+    let msg = ExecuteMsg::DistributeTaxReserve {
+        asset_info: reward_asset_info,
+        amount: Uint128::from(7u128),
+    };
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "distribute_tax_reserve"),
+            attr("asset_info", "uusd"),
+            attr("amount", "7"),
+        ]
+    );
+    assert_eq!(query_tax_reserve(&deps), Uint128::zero());
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UserInfo {
+            user: "addr".to_string(),
+        },
+    )
+    .unwrap();
+
+    let user_info_res: UserInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        user_info_res.pending_rewards[0].amount,
+        Uint128::from(7u128)
+    );
+}
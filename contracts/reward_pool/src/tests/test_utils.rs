@@ -1,45 +1,184 @@
-use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
-use cosmwasm_std::{to_binary, OwnedDeps, StdResult, Uint128};
-
-use crate::{
-    contract::{execute, instantiate},
-    tests::mock_querier::WasmMockQuerier,
-};
-use cw20::Cw20ReceiveMsg;
-use reward_pool::reward_pool::{Cw20HookMsg, ExecuteMsg, InstantiateMsg};
-use terraswap::asset::AssetInfo;
-
-pub fn instantiate_reward_pool(
-    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
-    reward_asset_info: AssetInfo,
-) -> StdResult<()> {
-    let msg = InstantiateMsg {
-        governance: String::from("governance"),
-        funder: String::from("funder"),
-        staking_token: String::from("staking_token"),
-        reward_asset_info: reward_asset_info.clone(),
-    };
-
-    let info = mock_info("policy", &[]);
-
-    instantiate(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
-
-    Ok(())
-}
-
-pub fn deposit(
-    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
-    amount: Uint128,
-) -> StdResult<()> {
-    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-        sender: "addr".to_string(),
-        msg: to_binary(&Cw20HookMsg::Deposit {}).unwrap(),
-        amount,
-    });
-
-    let info = mock_info("staking_token", &[]);
-
-    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-    Ok(())
-}
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{
+    coin, from_binary, to_binary, Decimal256, Env, OwnedDeps, Response, StdResult, Timestamp,
+    Uint128,
+};
+
+use crate::{
+    contract::{execute, instantiate, query},
+    error::ContractError,
+    tests::mock_querier::WasmMockQuerier,
+};
+use cw20::Cw20ReceiveMsg;
+use reward_pool::reward_pool::{
+    Cw20HookMsg, ExecuteMsg, ForfeitMode, InstantiateMsg, LockTier, QueryMsg, Unbond,
+    UnbondsResponse, UserInfoResponse,
+};
+use terraswap::asset::AssetInfo;
+
+pub fn mock_env_at(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(seconds);
+    env
+}
+
+pub fn mock_env_at_height(height: u64) -> Env {
+    let mut env = mock_env();
+    env.block.height = height;
+    env
+}
+
+pub const MONTH: u64 = 30 * 24 * 60 * 60;
+
+pub fn lock_tiers() -> Vec<LockTier> {
+    vec![
+        LockTier {
+            duration: MONTH,
+            boost: Decimal256::percent(150),
+        },
+        LockTier {
+            duration: 12 * MONTH,
+            boost: Decimal256::percent(300),
+        },
+    ]
+}
+
+pub fn uusd() -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    }
+}
+
+pub fn instantiate_reward_pool(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    reward_asset_info: AssetInfo,
+) -> StdResult<()> {
+    let msg = InstantiateMsg {
+        governance: String::from("governance"),
+        guardian: None,
+        funders: vec![String::from("funder")],
+        staking_asset_info: AssetInfo::Token {
+            contract_addr: String::from("staking_token"),
+        },
+        reward_asset_infos: vec![reward_asset_info.clone()],
+        lock_tiers: lock_tiers(),
+        unbonding_period: 0,
+        forfeit_mode: ForfeitMode::Redistribute,
+        permissionless_funding: false,
+        min_fund_amounts: vec![],
+        cw4_enabled: false,
+        tokens_per_weight: Uint128::from(1u128),
+        receipt_token: None,
+    };
+
+    let info = mock_info("policy", &[]);
+
+    instantiate(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+
+    Ok(())
+}
+
+pub fn deposit(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    amount: Uint128,
+) -> StdResult<()> {
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr".to_string(),
+        msg: to_binary(&Cw20HookMsg::Deposit {
+            recipient: None,
+            lock_duration: None,
+        })
+        .unwrap(),
+        amount,
+    });
+
+    let info = mock_info("staking_token", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    Ok(())
+}
+
+pub fn deposit_at(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    user: &str,
+    amount: u128,
+    height: u64,
+) {
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: user.to_string(),
+        msg: to_binary(&Cw20HookMsg::Deposit {
+            recipient: None,
+            lock_duration: None,
+        })
+        .unwrap(),
+        amount: Uint128::from(amount),
+    });
+
+    let info = mock_info("staking_token", &[]);
+
+    execute(deps.as_mut(), mock_env_at_height(height), info, msg).unwrap();
+}
+
+/// Funds `amount` of the reward asset from `funder`
+pub fn fund(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    funder: &str,
+    asset_info: AssetInfo,
+    amount: u128,
+) -> Result<Response, ContractError> {
+    match asset_info {
+        AssetInfo::NativeToken { denom } => {
+            let info = mock_info(funder, &[coin(amount, denom)]);
+
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Fund {})
+        }
+        AssetInfo::Token { contract_addr } => {
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: funder.to_string(),
+                msg: to_binary(&Cw20HookMsg::Fund {}).unwrap(),
+                amount: Uint128::from(amount),
+            });
+
+            let info = mock_info(&contract_addr, &[]);
+
+            execute(deps.as_mut(), mock_env(), info, msg)
+        }
+    }
+}
+
+/// Pending amount of the first reward asset
+pub fn query_pending_reward(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    env: Env,
+    user: &str,
+) -> Uint128 {
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::UserInfo {
+            user: user.to_string(),
+        },
+    )
+    .unwrap();
+
+    let user_info_res: UserInfoResponse = from_binary(&res).unwrap();
+    user_info_res.pending_rewards[0].amount
+}
+
+pub fn query_unbonds(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    user: &str,
+) -> Vec<Unbond> {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Unbonds {
+            user: user.to_string(),
+        },
+    )
+    .unwrap();
+
+    let unbonds_res: UnbondsResponse = from_binary(&res).unwrap();
+    unbonds_res.unbonds
+}
@@ -0,0 +1,335 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Coin, CosmosMsg, OverflowError, OverflowOperation, OwnedDeps,
+    StdError, SubMsg, Uint128, WasmMsg,
+};
+
+use cw20::Cw20ExecuteMsg;
+use reward_pool::reward_pool::{ExecuteMsg, QueryMsg, State, Unbond, UserInfoResponse};
+use terraswap::asset::AssetInfo;
+
+use crate::{
+    contract::{execute, query},
+    error::ContractError,
+    tests::{
+        mock_querier::{mock_dependencies, WasmMockQuerier},
+        test_utils::{deposit, instantiate_reward_pool, mock_env_at, query_unbonds},
+    },
+};
+
+const UNBONDING_PERIOD: u64 = 100;
+
+fn instantiate_with_unbonding(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) {
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(deps, reward_asset_info).unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        guardian: None,
+        unbonding_period: Some(UNBONDING_PERIOD),
+        forfeit_mode: None,
+        permissionless_funding: None,
+        min_fund_amounts: None,
+        cw4_enabled: None,
+        tokens_per_weight: None,
+    };
+
+    let info = mock_info("governance", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+#[test]
+fn create_unbond_on_withdraw() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_with_unbonding(&mut deps);
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let now = mock_env().block.time.seconds();
+    for (withdraw_amount, time) in [(30u128, now), (20u128, now + 10)] {
+        let msg = ExecuteMsg::Withdraw {
+            amount: Uint128::from(withdraw_amount),
+            recipient: None,
+        };
+
+        let info = mock_info("addr", &[]);
+
+        let res = execute(deps.as_mut(), mock_env_at(time), info, msg).unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "withdraw"),
+                attr("recipient", "addr"),
+                attr("amount", withdraw_amount.to_string()),
+                attr("release_time", (time + UNBONDING_PERIOD).to_string()),
+            ]
+        );
+    }
+
+    assert_eq!(
+        query_unbonds(&deps, "addr"),
+        vec![
+            Unbond {
+                amount: Uint128::from(30u128),
+                release_time: now + UNBONDING_PERIOD,
+                recipient: String::from("addr"),
+            },
+            Unbond {
+                amount: Uint128::from(20u128),
+                release_time: now + 10 + UNBONDING_PERIOD,
+                recipient: String::from("addr"),
+            },
+        ]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(state_res.total_deposits, Uint128::from(50u128));
+    assert_eq!(state_res.total_weight, Uint128::from(50u128));
+    assert_eq!(state_res.total_unbonding, Uint128::from(50u128));
+}
+
+#[test]
+fn unbonding_amount_does_not_earn_rewards() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_with_unbonding(&mut deps);
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: Uint128::from(100u128),
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::Fund {};
+
+    let info = mock_info(
+        "funder",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(100u128),
+        }],
+    );
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UserInfo {
+            user: "addr".to_string(),
+        },
+    )
+    .unwrap();
+
+    let user_info_res: UserInfoResponse = from_binary(&res).unwrap();
+
+    assert_eq!(user_info_res.stake_amount, Uint128::zero());
+    assert_eq!(user_info_res.pending_rewards[0].amount, Uint128::zero());
+}
+
+#[test]
+fn fails_to_claim_before_release_time() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_with_unbonding(&mut deps);
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: Uint128::from(100u128),
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::ClaimUnbonded {};
+
+    let info = mock_info("addr", &[]);
+
+    let now = mock_env().block.time.seconds();
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at(now + UNBONDING_PERIOD - 1),
+        info,
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::NoReleasedUnbonds {});
+}
+
+#[test]
+fn fails_if_release_time_overflows() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_with_unbonding(&mut deps);
+
+    let msg = ExecuteMsg::UpdateConfig {
+        guardian: None,
+        unbonding_period: Some(u64::MAX),
+        forfeit_mode: None,
+        permissionless_funding: None,
+        min_fund_amounts: None,
+        cw4_enabled: None,
+        tokens_per_weight: None,
+    };
+
+    let info = mock_info("governance", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: Uint128::from(100u128),
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::Std(StdError::overflow(OverflowError::new(
+            OverflowOperation::Add,
+            mock_env().block.time.seconds(),
+            u64::MAX,
+        )))
+    );
+}
+
+#[test]
+fn claim_released_unbonds() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_with_unbonding(&mut deps);
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let now = mock_env().block.time.seconds();
+    for (withdraw_amount, time) in [(30u128, now), (20u128, now + 10)] {
+        let msg = ExecuteMsg::Withdraw {
+            amount: Uint128::from(withdraw_amount),
+            recipient: None,
+        };
+
+        let info = mock_info("addr", &[]);
+
+        execute(deps.as_mut(), mock_env_at(time), info, msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::ClaimUnbonded {};
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at(now + UNBONDING_PERIOD),
+        info,
+        msg,
+    )
+    .unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("staking_token"),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: String::from("addr"),
+                amount: Uint128::from(30u128),
+            })
+            .unwrap(),
+        }))]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "claim_unbonded"), attr("amount", "30")]
+    );
+
+    assert_eq!(
+        query_unbonds(&deps, "addr"),
+        vec![Unbond {
+            amount: Uint128::from(20u128),
+            release_time: now + 10 + UNBONDING_PERIOD,
+            recipient: String::from("addr"),
+        }]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(state_res.total_unbonding, Uint128::from(20u128));
+}
+
+#[test]
+fn claim_released_unbonds_to_recipients() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_with_unbonding(&mut deps);
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let info = mock_info("addr", &[]);
+
+    for (amount, recipient) in [
+        (10u128, Some("wallet")),
+        (20u128, None),
+        (30u128, Some("wallet")),
+    ] {
+        let msg = ExecuteMsg::Withdraw {
+            amount: Uint128::from(amount),
+            recipient: recipient.map(String::from),
+        };
+
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    }
+
+    let now = mock_env().block.time.seconds();
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at(now + UNBONDING_PERIOD),
+        info,
+        ExecuteMsg::ClaimUnbonded {},
+    )
+    .unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("staking_token"),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("wallet"),
+                    amount: Uint128::from(40u128),
+                })
+                .unwrap(),
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("staking_token"),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("addr"),
+                    amount: Uint128::from(20u128),
+                })
+                .unwrap(),
+            })),
+        ]
+    );
+}
@@ -1,221 +1,463 @@
-use cosmwasm_std::testing::{mock_env, mock_info};
-use cosmwasm_std::{
-    attr, from_binary, to_binary, CosmosMsg, Decimal, StdError, SubMsg, Uint128, WasmMsg,
-};
-
-use cw20::Cw20ExecuteMsg;
-use reward_pool::reward_pool::{ExecuteMsg, QueryMsg, State, UserInfoResponse};
-use terraswap::asset::AssetInfo;
-
-use crate::{
-    contract::{execute, query},
-    state::STATE,
-    tests::{
-        mock_querier::mock_dependencies,
-        test_utils::{deposit, instantiate_reward_pool},
-    },
-};
-
-#[test]
-fn fails_if_amount_is_zero() {
-    let mut deps = mock_dependencies(&[]);
-
-    let reward_asset_info = AssetInfo::NativeToken {
-        denom: "uusd".to_string(),
-    };
-
-    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
-
-    let msg = ExecuteMsg::Withdraw {
-        amount: Uint128::zero(),
-    };
-
-    let info = mock_info("addr", &[]);
-
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
-    assert_eq!(res, StdError::generic_err("amount is zero"));
-}
-
-#[test]
-fn decrease_total_deposits() {
-    let mut deps = mock_dependencies(&[]);
-
-    let reward_asset_info = AssetInfo::NativeToken {
-        denom: "uusd".to_string(),
-    };
-
-    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
-
-    let withdraw_amount = Uint128::from(50u128);
-    let total_deposits = Uint128::from(100u128);
-
-    deposit(&mut deps, total_deposits).unwrap();
-
-    let msg = ExecuteMsg::Withdraw {
-        amount: withdraw_amount,
-    };
-
-    let info = mock_info("addr", &[]);
-
-    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
-
-    let state_res: State = from_binary(&res).unwrap();
-
-    assert_eq!(
-        State {
-            acc_per_share: Decimal::zero(),
-            total_deposits: total_deposits - withdraw_amount,
-        },
-        state_res
-    );
-}
-
-#[test]
-fn update_user_info() {
-    let mut deps = mock_dependencies(&[]);
-
-    let reward_asset_info = AssetInfo::NativeToken {
-        denom: "uusd".to_string(),
-    };
-
-    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
-
-    let stake_amount = Uint128::from(100u128);
-
-    deposit(&mut deps, stake_amount).unwrap();
-
-    let withdraw_amount = Uint128::from(50u128);
-    let total_deposits = Uint128::from(100u128);
-    let acc_per_share = Decimal::percent(50);
-
-    STATE
-        .save(
-            &mut deps.storage,
-            &State {
-                acc_per_share,
-                total_deposits,
-            },
-        )
-        .unwrap();
-
-    let msg = ExecuteMsg::Withdraw {
-        amount: withdraw_amount,
-    };
-
-    let info = mock_info("addr", &[]);
-
-    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-    let res = query(
-        deps.as_ref(),
-        mock_env(),
-        QueryMsg::UserInfo {
-            user: "addr".to_string(),
-        },
-    )
-    .unwrap();
-
-    let user_info_res: UserInfoResponse = from_binary(&res).unwrap();
-
-    assert_eq!(
-        UserInfoResponse {
-            stake_amount: stake_amount - withdraw_amount,
-            pending_amount: stake_amount * acc_per_share,
-        },
-        user_info_res
-    );
-}
-
-#[test]
-fn transfer_staking_token() {
-    let mut deps = mock_dependencies(&[]);
-
-    let reward_asset_info = AssetInfo::NativeToken {
-        denom: "uusd".to_string(),
-    };
-
-    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
-
-    let stake_amount = Uint128::from(100u128);
-
-    deposit(&mut deps, stake_amount).unwrap();
-
-    let withdraw_amount = Uint128::from(50u128);
-    let total_deposits = Uint128::from(100u128);
-    let acc_per_share = Decimal::percent(50);
-
-    STATE
-        .save(
-            &mut deps.storage,
-            &State {
-                acc_per_share,
-                total_deposits,
-            },
-        )
-        .unwrap();
-
-    let msg = ExecuteMsg::Withdraw {
-        amount: withdraw_amount,
-    };
-
-    let info = mock_info("addr", &[]);
-
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-    assert_eq!(
-        res.messages,
-        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: String::from("staking_token"),
-            funds: vec![],
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: String::from("addr"),
-                amount: withdraw_amount
-            })
-            .unwrap(),
-        })),]
-    );
-}
-
-#[test]
-fn return_correct_logs() {
-    let mut deps = mock_dependencies(&[]);
-
-    let reward_asset_info = AssetInfo::NativeToken {
-        denom: "uusd".to_string(),
-    };
-
-    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
-
-    let stake_amount = Uint128::from(100u128);
-
-    deposit(&mut deps, stake_amount).unwrap();
-
-    let withdraw_amount = Uint128::from(50u128);
-    let total_deposits = Uint128::from(100u128);
-    let acc_per_share = Decimal::percent(50);
-
-    STATE
-        .save(
-            &mut deps.storage,
-            &State {
-                acc_per_share,
-                total_deposits,
-            },
-        )
-        .unwrap();
-
-    let msg = ExecuteMsg::Withdraw {
-        amount: withdraw_amount,
-    };
-
-    let info = mock_info("addr", &[]);
-
-    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-
-    assert_eq!(
-        res.attributes,
-        vec![attr("action", "withdraw"), attr("amount", withdraw_amount),]
-    );
-}
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Api, CosmosMsg, Decimal256, SubMsg, Uint128, WasmMsg,
+};
+
+use cw20::Cw20ExecuteMsg;
+use reward_pool::reward_pool::{ExecuteMsg, QueryMsg, RewardState, State, UserInfoResponse};
+use terraswap::asset::{Asset, AssetInfo};
+
+use crate::{
+    contract::{execute, query},
+    error::ContractError,
+    state::{STATE, USER_INFO},
+    tests::{
+        mock_querier::mock_dependencies,
+        test_utils::{deposit, fund, instantiate_reward_pool, uusd},
+    },
+    utils::compute_reward,
+};
+
+#[test]
+fn fails_if_amount_is_zero() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: Uint128::zero(),
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(res, ContractError::ZeroAmount {});
+}
+
+#[test]
+fn fails_if_amount_exceeds_stake() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let stake_amount = Uint128::from(100u128);
+
+    deposit(&mut deps, stake_amount).unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: stake_amount + Uint128::from(1u128),
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InsufficientStake {
+            requested: Uint128::from(101u128),
+            available: stake_amount,
+        }
+    );
+}
+
+#[test]
+fn fails_if_user_has_no_stake() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: Uint128::from(1u128),
+        recipient: None,
+    };
+
+    let info = mock_info("other", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InsufficientStake {
+            requested: Uint128::from(1u128),
+            available: Uint128::zero(),
+        }
+    );
+}
+
+#[test]
+fn withdraw_entire_stake() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let stake_amount = Uint128::from(100u128);
+
+    deposit(&mut deps, stake_amount).unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: stake_amount,
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UserInfo {
+            user: "addr".to_string(),
+        },
+    )
+    .unwrap();
+
+    let user_info_res: UserInfoResponse = from_binary(&res).unwrap();
+
+    assert_eq!(user_info_res.stake_amount, Uint128::zero());
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(state_res.total_deposits, Uint128::zero());
+}
+
+#[test]
+fn remove_user_info_without_stake_and_rewards() {
+    let mut deps = mock_dependencies(&[]);
+
+    instantiate_reward_pool(&mut deps, uusd()).unwrap();
+
+    deposit(&mut deps, Uint128::from(100u128)).unwrap();
+    fund(&mut deps, "funder", uusd(), 100).unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: Uint128::from(100u128),
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    // the pending rewards keep the user info
+    let user_raw = deps.api.addr_canonicalize("addr").unwrap();
+    let user_info = USER_INFO.load(&deps.storage, user_raw.as_slice()).unwrap();
+    assert_eq!(user_info.stake_amount, Uint128::zero());
+    assert_eq!(user_info.rewards[0].pending_amount, Uint128::from(100u128));
+
+    let msg = ExecuteMsg::Claim {
+        recipient: None,
+        swap: None,
+    };
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        USER_INFO
+            .may_load(&deps.storage, user_raw.as_slice())
+            .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn decrease_total_deposits() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let withdraw_amount = Uint128::from(50u128);
+    let total_deposits = Uint128::from(100u128);
+
+    deposit(&mut deps, total_deposits).unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: withdraw_amount,
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+
+    let state_res: State = from_binary(&res).unwrap();
+
+    assert_eq!(
+        State {
+            total_deposits: total_deposits - withdraw_amount,
+            total_weight: total_deposits - withdraw_amount,
+            total_unbonding: Uint128::zero(),
+            staker_count: 1,
+            rewards: vec![RewardState {
+                acc_per_share: Decimal256::zero(),
+                undistributed_amount: Uint128::zero(),
+                distributed_amount: Uint128::zero(),
+                claimed_amount: Uint128::zero(),
+                forfeited_amount: Uint128::zero(),
+                tax_reserve: Uint128::zero(),
+            }],
+        },
+        state_res
+    );
+}
+
+#[test]
+fn update_user_info() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let stake_amount = Uint128::from(100u128);
+
+    deposit(&mut deps, stake_amount).unwrap();
+
+    let withdraw_amount = Uint128::from(50u128);
+    let total_deposits = Uint128::from(100u128);
+    let acc_per_share = Decimal256::percent(50);
+
+    STATE
+        .save(
+            &mut deps.storage,
+            &State {
+                total_deposits,
+                total_weight: total_deposits,
+                total_unbonding: Uint128::zero(),
+                staker_count: 0,
+                rewards: vec![RewardState {
+                    acc_per_share,
+                    undistributed_amount: Uint128::zero(),
+                    distributed_amount: Uint128::zero(),
+                    claimed_amount: Uint128::zero(),
+                    forfeited_amount: Uint128::zero(),
+                    tax_reserve: Uint128::zero(),
+                }],
+            },
+        )
+        .unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: withdraw_amount,
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UserInfo {
+            user: "addr".to_string(),
+        },
+    )
+    .unwrap();
+
+    let user_info_res: UserInfoResponse = from_binary(&res).unwrap();
+
+    assert_eq!(
+        UserInfoResponse {
+            stake_amount: stake_amount - withdraw_amount,
+            pending_rewards: vec![Asset {
+                info: reward_asset_info.clone(),
+                amount: compute_reward(stake_amount, acc_per_share).unwrap(),
+            }],
+            auto_compound: false,
+        },
+        user_info_res
+    );
+}
+
+#[test]
+fn transfer_staking_token() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let stake_amount = Uint128::from(100u128);
+
+    deposit(&mut deps, stake_amount).unwrap();
+
+    let withdraw_amount = Uint128::from(50u128);
+    let total_deposits = Uint128::from(100u128);
+    let acc_per_share = Decimal256::percent(50);
+
+    STATE
+        .save(
+            &mut deps.storage,
+            &State {
+                total_deposits,
+                total_weight: total_deposits,
+                total_unbonding: Uint128::zero(),
+                staker_count: 0,
+                rewards: vec![RewardState {
+                    acc_per_share,
+                    undistributed_amount: Uint128::zero(),
+                    distributed_amount: Uint128::zero(),
+                    claimed_amount: Uint128::zero(),
+                    forfeited_amount: Uint128::zero(),
+                    tax_reserve: Uint128::zero(),
+                }],
+            },
+        )
+        .unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: withdraw_amount,
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("staking_token"),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: String::from("addr"),
+                amount: withdraw_amount
+            })
+            .unwrap(),
+        })),]
+    );
+}
+
+#[test]
+fn return_correct_logs() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let stake_amount = Uint128::from(100u128);
+
+    deposit(&mut deps, stake_amount).unwrap();
+
+    let withdraw_amount = Uint128::from(50u128);
+    let total_deposits = Uint128::from(100u128);
+    let acc_per_share = Decimal256::percent(50);
+
+    STATE
+        .save(
+            &mut deps.storage,
+            &State {
+                total_deposits,
+                total_weight: total_deposits,
+                total_unbonding: Uint128::zero(),
+                staker_count: 0,
+                rewards: vec![RewardState {
+                    acc_per_share,
+                    undistributed_amount: Uint128::zero(),
+                    distributed_amount: Uint128::zero(),
+                    claimed_amount: Uint128::zero(),
+                    forfeited_amount: Uint128::zero(),
+                    tax_reserve: Uint128::zero(),
+                }],
+            },
+        )
+        .unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: withdraw_amount,
+        recipient: None,
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "withdraw"),
+            attr("recipient", "addr"),
+            attr("amount", withdraw_amount),
+        ]
+    );
+}
+
+#[test]
+fn withdraw_to_recipient() {
+    let mut deps = mock_dependencies(&[]);
+
+    let reward_asset_info = AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    };
+
+    instantiate_reward_pool(&mut deps, reward_asset_info.clone()).unwrap();
+
+    let stake_amount = Uint128::from(100u128);
+
+    deposit(&mut deps, stake_amount).unwrap();
+
+    let msg = ExecuteMsg::Withdraw {
+        amount: stake_amount,
+        recipient: Some("wallet".to_string()),
+    };
+
+    let info = mock_info("addr", &[]);
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "withdraw"),
+            attr("recipient", "wallet"),
+            attr("amount", stake_amount),
+        ]
+    );
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("staking_token"),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: String::from("wallet"),
+                amount: stake_amount
+            })
+            .unwrap(),
+        })),]
+    );
+}
@@ -1,24 +1,606 @@
-use cosmwasm_std::{MessageInfo, StdError, StdResult, Storage, Uint128};
-
-use terraswap::asset::AssetInfoRaw;
-
-use crate::state::CONFIGURATION;
-
-pub fn get_received_native_fund(storage: &dyn Storage, info: MessageInfo) -> StdResult<Uint128> {
-    let config = CONFIGURATION.load(storage)?;
-
-    if info.funds.len() != 1u64 as usize {
-        return Err(StdError::generic_err("invalid denom received"));
-    }
-    if let AssetInfoRaw::NativeToken { denom } = config.reward_asset_info {
-        let amount: Uint128 = info
-            .funds
-            .iter()
-            .find(|c| c.denom == *denom)
-            .map(|c| Uint128::from(c.amount))
-            .unwrap_or_else(Uint128::zero);
-        Ok(amount)
-    } else {
-        Err(StdError::generic_err("not support denom reward"))
-    }
-}
+use std::convert::TryFrom;
+
+use cosmwasm_std::{
+    Addr, Api, BankMsg, CanonicalAddr, Coin, CosmosMsg, Decimal, Decimal256, Fraction, MessageInfo,
+    Order, OverflowError, OverflowOperation, QuerierWrapper, StdError, StdResult, Storage, Uint128,
+    Uint256,
+};
+
+use cw_storage_plus::{Bound, U64Key};
+use reward_pool::reward_pool::{LockPosition, RewardSchedule, RewardState, State, UserReward};
+use terra_cosmwasm::TerraQuerier;
+use terraswap::asset::{Asset, AssetInfo, AssetInfoRaw};
+
+use crate::{
+    error::ContractError,
+    state::{
+        Config, FunderInfo, FunderReward, LockExpiry, UserInfo, CONFIGURATION, LOCK_EXPIRIES,
+        TOTAL_STAKE, TOTAL_STAKE_KEY, USER_INFO, USER_STAKES,
+    },
+};
+
+/// Returns the validated `recipient`, or `sender` if not set
+pub fn validate_recipient(
+    api: &dyn Api,
+    recipient: Option<String>,
+    sender: &str,
+) -> StdResult<String> {
+    match recipient {
+        Some(recipient) => Ok(api.addr_validate(&recipient)?.to_string()),
+        None => Ok(sender.to_string()),
+    }
+}
+
+/// Whether `addr` is the receipt token of the pool
+pub fn is_receipt_token(api: &dyn Api, config: &Config, addr: &str) -> StdResult<bool> {
+    match &config.receipt_token {
+        Some(receipt_token) => Ok(api.addr_humanize(receipt_token)? == addr),
+        None => Ok(false),
+    }
+}
+
+/// Returns the amount of the received staking denom
+pub fn get_received_native_stake(
+    config: &Config,
+    info: &MessageInfo,
+) -> Result<Uint128, ContractError> {
+    if info.funds.len() != 1u64 as usize {
+        return Err(ContractError::InvalidDenomReceived {
+            received: info.funds.len(),
+        });
+    }
+    let coin = &info.funds[0];
+    match &config.staking_asset_info {
+        AssetInfoRaw::NativeToken { denom } if *denom == coin.denom => Ok(coin.amount),
+        _ => Err(ContractError::InvalidStakingToken {
+            token: coin.denom.clone(),
+        }),
+    }
+}
+
+/// Returns the index of the received reward denom and its amount
+pub fn get_received_native_fund(
+    storage: &dyn Storage,
+    info: MessageInfo,
+) -> Result<(usize, Uint128), ContractError> {
+    let config = CONFIGURATION.load(storage)?;
+
+    if info.funds.len() != 1u64 as usize {
+        return Err(ContractError::InvalidDenomReceived {
+            received: info.funds.len(),
+        });
+    }
+    let coin = &info.funds[0];
+    config
+        .reward_asset_infos
+        .iter()
+        .position(|asset_info| match asset_info {
+            AssetInfoRaw::NativeToken { denom } => *denom == coin.denom,
+            AssetInfoRaw::Token { .. } => false,
+        })
+        .map(|reward_index| (reward_index, coin.amount))
+        .ok_or_else(|| ContractError::UnsupportedDenomReward {
+            denom: coin.denom.clone(),
+        })
+}
+
+/// Returns the index of the reward token which sent the cw20 hook
+pub fn get_reward_token_index(
+    api: &dyn Api,
+    config: &Config,
+    token: &str,
+) -> Result<usize, ContractError> {
+    for (reward_index, asset_info) in config.reward_asset_infos.iter().enumerate() {
+        if let AssetInfoRaw::Token { contract_addr } = asset_info {
+            if api.addr_humanize(contract_addr)? == token {
+                return Ok(reward_index);
+            }
+        }
+    }
+    Err(ContractError::InvalidRewardToken {
+        token: token.to_string(),
+    })
+}
+
+/// Returns the index of the reward asset which is the staking asset
+pub fn get_staking_reward_index(config: &Config) -> Option<usize> {
+    config
+        .reward_asset_infos
+        .iter()
+        .position(|asset_info| asset_info.equal(&config.staking_asset_info))
+}
+
+/// Tax which the pool sets aside from a funding of `asset` to pay for its distribution.
+/// The tax cap is not applied, as the funding is paid out in many transfers,
+/// so the reserve can exceed the tax actually paid.
+pub fn compute_reserved_tax(querier: &QuerierWrapper, asset: &Asset) -> StdResult<Uint128> {
+    match &asset.info {
+        AssetInfo::NativeToken { denom } if denom != "uluna" => {
+            let tax_rate = TerraQuerier::new(querier).query_tax_rate()?.rate;
+            let decimal_fraction = Uint128::from(Decimal::one().denominator());
+            Ok(asset.amount.checked_sub(asset.amount.multiply_ratio(
+                decimal_fraction,
+                decimal_fraction * (Decimal::one() + tax_rate),
+            ))?)
+        }
+        _ => Ok(Uint128::zero()),
+    }
+}
+
+/// Tax which the pool pays on top of a transfer of `asset`
+pub fn compute_transfer_tax(querier: &QuerierWrapper, asset: &Asset) -> StdResult<Uint128> {
+    match &asset.info {
+        AssetInfo::NativeToken { denom } if denom != "uluna" => {
+            let terra_querier = TerraQuerier::new(querier);
+            let tax_rate = terra_querier.query_tax_rate()?.rate;
+            let tax_cap = terra_querier.query_tax_cap(denom.to_string())?.cap;
+            Ok(std::cmp::min(asset.amount * tax_rate, tax_cap))
+        }
+        _ => Ok(Uint128::zero()),
+    }
+}
+
+/// Pays the tax of the payout `asset` out of the tax reserve of the reward, or deducts it
+/// from the payout if the reserve is short. Returns the payout to send and its tax.
+pub fn pay_reward_tax(
+    querier: &QuerierWrapper,
+    reward: &mut RewardState,
+    asset: Asset,
+) -> StdResult<(Asset, Uint128)> {
+    let tax_amount = compute_transfer_tax(querier, &asset)?;
+    if tax_amount <= reward.tax_reserve {
+        reward.tax_reserve = reward.tax_reserve.checked_sub(tax_amount)?;
+        return Ok((asset, tax_amount));
+    }
+
+    let coin = asset.deduct_tax(querier)?;
+    Ok((
+        Asset {
+            info: asset.info,
+            amount: coin.amount,
+        },
+        asset.amount.checked_sub(coin.amount)?,
+    ))
+}
+
+/// Transfer message of a reward payout, native rewards are sent after `pay_reward_tax`
+pub fn into_reward_msg(
+    querier: &QuerierWrapper,
+    reward: &mut RewardState,
+    asset: Asset,
+    recipient: Addr,
+) -> StdResult<CosmosMsg> {
+    let (asset, _) = pay_reward_tax(querier, reward, asset)?;
+    match &asset.info {
+        AssetInfo::NativeToken { denom } => Ok(CosmosMsg::Bank(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin {
+                denom: denom.to_string(),
+                amount: asset.amount,
+            }],
+        })),
+        AssetInfo::Token { .. } => asset.into_msg(querier, recipient),
+    }
+}
+
+/// Reward amount of `stake_amount` for `acc_per_share`, rounded down
+pub fn compute_reward(stake_amount: Uint128, acc_per_share: Decimal256) -> StdResult<Uint128> {
+    Ok(Uint128::try_from(
+        Uint256::from(stake_amount) * acc_per_share,
+    )?)
+}
+
+/// Adds `amount` to the `acc_per_share` of the reward together with the carried over rewards.
+/// While there are no deposits the rewards are kept as `undistributed_amount`,
+/// otherwise only the rounding remainder of the distribution is kept there.
+pub fn distribute_reward(state: &mut State, reward_index: usize, amount: Uint128) -> StdResult<()> {
+    let total_weight = state.total_weight;
+    let reward = &mut state.rewards[reward_index];
+    reward.undistributed_amount = reward.undistributed_amount.checked_add(amount)?;
+
+    if total_weight.is_zero() || reward.undistributed_amount.is_zero() {
+        return Ok(());
+    }
+
+    let acc_per_share = Decimal256::from_ratio(reward.undistributed_amount, total_weight);
+    // the rounded down `acc_per_share` covers at most `acc_per_share * total_weight`,
+    // so rounding it up keeps the remainder below the real one
+    let fractional = Decimal256::one().denominator();
+    let distributed_amount = Uint128::try_from(
+        acc_per_share
+            .numerator()
+            .checked_mul(Uint256::from(total_weight))?
+            .checked_add(fractional - Uint256::from(1u8))?
+            / fractional,
+    )?;
+
+    reward.acc_per_share = Decimal256::from_ratio(
+        reward
+            .acc_per_share
+            .numerator()
+            .checked_add(acc_per_share.numerator())?,
+        fractional,
+    );
+    reward.distributed_amount = reward.distributed_amount.checked_add(distributed_amount)?;
+    reward.undistributed_amount = reward
+        .undistributed_amount
+        .checked_sub(distributed_amount)?;
+
+    Ok(())
+}
+
+/// Distributes the rewards emitted by `schedules` until `time`
+/// and drops the schedules which are fully released.
+pub fn release_scheduled_rewards(
+    state: &mut State,
+    schedules: &mut Vec<RewardSchedule>,
+    time: u64,
+) -> StdResult<()> {
+    let mut released_amounts = vec![Uint128::zero(); state.rewards.len()];
+    for schedule in schedules.iter_mut() {
+        let vested_amount = schedule.vested_amount(time);
+        // lock expiries left over from a previous update are released before its time
+        if vested_amount <= schedule.released_amount {
+            continue;
+        }
+        let released_amount = &mut released_amounts[schedule.reward_index as usize];
+        *released_amount =
+            released_amount.checked_add(vested_amount.checked_sub(schedule.released_amount)?)?;
+        schedule.released_amount = vested_amount;
+    }
+    schedules.retain(|schedule| schedule.released_amount < schedule.amount);
+
+    for (reward_index, released_amount) in released_amounts.into_iter().enumerate() {
+        if !released_amount.is_zero() {
+            distribute_reward(state, reward_index, released_amount)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Maximum number of expired users whose boost is dropped in a single update,
+/// the rest is dropped in the following updates
+const MAX_LOCK_EXPIRIES: usize = 30;
+
+/// Users whose expired lock positions were dropped while releasing the rewards,
+/// which the caller has to save together with the processed `expiries`
+#[derive(Default)]
+pub struct ReleasedLocks {
+    pub users: Vec<(CanonicalAddr, UserInfo)>,
+    /// Processed expiries with the users which are left for the next update
+    pub expiries: Vec<LockExpiry>,
+}
+
+impl ReleasedLocks {
+    pub fn user_info(&self, user_raw: &CanonicalAddr) -> Option<&UserInfo> {
+        self.users
+            .iter()
+            .find(|(raw, _)| raw == user_raw)
+            .map(|(_, user_info)| user_info)
+    }
+}
+
+/// Distributes the streamed rewards until `time`. The boost of lock positions
+/// expiring before is dropped at their end time, so the rewards released afterwards
+/// are shared by the unboosted weights.
+pub fn release_rewards(
+    storage: &dyn Storage,
+    state: &mut State,
+    schedules: &mut Vec<RewardSchedule>,
+    time: u64,
+) -> StdResult<ReleasedLocks> {
+    let mut released = ReleasedLocks::default();
+    let mut remaining = MAX_LOCK_EXPIRIES;
+    let expiries = LOCK_EXPIRIES.range(
+        storage,
+        None,
+        Some(Bound::inclusive(U64Key::from(time))),
+        Order::Ascending,
+    );
+    for item in expiries {
+        if remaining == 0 {
+            break;
+        }
+        let (_, mut expiry) = item?;
+        release_scheduled_rewards(state, schedules, expiry.end_time)?;
+
+        let count = expiry.users.len().min(remaining);
+        remaining -= count;
+        for user_raw in expiry.users.drain(..count) {
+            let index = match released.users.iter().position(|(raw, _)| raw == &user_raw) {
+                Some(index) => index,
+                None => match USER_INFO.may_load(storage, user_raw.as_slice())? {
+                    Some(user_info) => {
+                        released.users.push((user_raw, user_info));
+                        released.users.len() - 1
+                    }
+                    None => continue,
+                },
+            };
+            let user_info = &mut released.users[index].1;
+            settle_rewards(state, user_info)?;
+            release_expired_locks(user_info, expiry.end_time);
+            update_weight(state, user_info)?;
+            reset_reward_debts(state, user_info)?;
+        }
+        released.expiries.push(expiry);
+    }
+    release_scheduled_rewards(state, schedules, time)?;
+
+    Ok(released)
+}
+
+/// Adds the user to the expiry of the lock positions ending at `end_time`
+pub fn save_lock_expiry(
+    storage: &mut dyn Storage,
+    user_raw: &CanonicalAddr,
+    end_time: u64,
+) -> StdResult<()> {
+    let mut expiry = LOCK_EXPIRIES
+        .may_load(storage, U64Key::from(end_time))?
+        .unwrap_or(LockExpiry {
+            end_time,
+            users: vec![],
+        });
+    if !expiry.users.contains(user_raw) {
+        expiry.users.push(user_raw.clone());
+        LOCK_EXPIRIES.save(storage, U64Key::from(end_time), &expiry)?;
+    }
+
+    Ok(())
+}
+
+/// Saves the user info, or removes it once the user has neither stake nor pending rewards
+/// so that the users which left the pool do not remain in the storage
+pub fn save_user_info(
+    storage: &mut dyn Storage,
+    user_raw: &CanonicalAddr,
+    user_info: &UserInfo,
+) -> StdResult<()> {
+    if user_info.stake_amount.is_zero()
+        && user_info
+            .rewards
+            .iter()
+            .all(|user_reward| user_reward.pending_amount.is_zero())
+    {
+        USER_INFO.remove(storage, user_raw.as_slice());
+        return Ok(());
+    }
+
+    USER_INFO.save(storage, user_raw.as_slice(), user_info)
+}
+
+/// Moves the rewards accrued since the last settlement into the pending amounts
+pub fn settle_rewards(state: &State, user_info: &mut UserInfo) -> StdResult<()> {
+    user_info
+        .rewards
+        .resize(state.rewards.len(), UserReward::default());
+    for (reward, user_reward) in state.rewards.iter().zip(user_info.rewards.iter_mut()) {
+        user_reward.pending_amount = user_reward.pending_amount.checked_add(
+            compute_reward(user_info.weight, reward.acc_per_share)?
+                .checked_sub(user_reward.reward_debt)?,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Moves the pending rewards of `reward_index`, which has to be the staking token,
+/// into the stake of the user. The weight has to be updated afterwards.
+pub fn compound_rewards(
+    state: &mut State,
+    user_info: &mut UserInfo,
+    reward_index: usize,
+) -> StdResult<Uint128> {
+    let amount = match user_info.rewards.get_mut(reward_index) {
+        Some(user_reward) => std::mem::take(&mut user_reward.pending_amount),
+        None => return Ok(Uint128::zero()),
+    };
+
+    let reward = &mut state.rewards[reward_index];
+    reward.claimed_amount = reward.claimed_amount.checked_add(amount)?;
+    user_info.stake_amount = user_info.stake_amount.checked_add(amount)?;
+    state.total_deposits = state.total_deposits.checked_add(amount)?;
+
+    Ok(amount)
+}
+
+/// Returns `time` advanced by `seconds`, failing instead of wrapping on overflow
+pub fn add_seconds(time: u64, seconds: u64) -> StdResult<u64> {
+    time.checked_add(seconds).ok_or_else(|| {
+        StdError::overflow(OverflowError::new(OverflowOperation::Add, time, seconds))
+    })
+}
+
+/// Counts the user in or out of `staker_count` when the stake became non-zero or zero
+pub fn update_staker_count(
+    state: &mut State,
+    prev_stake_amount: Uint128,
+    stake_amount: Uint128,
+) -> StdResult<()> {
+    match (prev_stake_amount.is_zero(), stake_amount.is_zero()) {
+        (true, false) => {
+            state.staker_count = state.staker_count.checked_add(1).ok_or_else(|| {
+                StdError::overflow(OverflowError::new(
+                    OverflowOperation::Add,
+                    state.staker_count,
+                    1,
+                ))
+            })?
+        }
+        (false, true) => {
+            state.staker_count = state.staker_count.checked_sub(1).ok_or_else(|| {
+                StdError::overflow(OverflowError::new(
+                    OverflowOperation::Sub,
+                    state.staker_count,
+                    1,
+                ))
+            })?
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Snapshots the stake of the user and `total_deposits` at `height`
+pub fn snapshot_stakes(
+    storage: &mut dyn Storage,
+    user_raw: &CanonicalAddr,
+    stake_amount: Uint128,
+    total_deposits: Uint128,
+    height: u64,
+) -> StdResult<()> {
+    USER_STAKES.save(storage, user_raw.as_slice(), &stake_amount, height)?;
+    TOTAL_STAKE.save(storage, TOTAL_STAKE_KEY, &total_deposits, height)
+}
+
+/// Resets the reward debts after `weight` was changed
+pub fn reset_reward_debts(state: &State, user_info: &mut UserInfo) -> StdResult<()> {
+    for (reward, user_reward) in state.rewards.iter().zip(user_info.rewards.iter_mut()) {
+        user_reward.reward_debt = compute_reward(user_info.weight, reward.acc_per_share)?;
+    }
+
+    Ok(())
+}
+
+/// Converts the minimum fund amounts to the order of `reward_asset_infos`
+pub fn to_min_fund_amounts(
+    api: &dyn Api,
+    reward_asset_infos: &[AssetInfoRaw],
+    min_fund_amounts: &[Asset],
+) -> Result<Vec<Uint128>, ContractError> {
+    let mut amounts = vec![Uint128::zero(); reward_asset_infos.len()];
+    for min_fund_amount in min_fund_amounts.iter() {
+        let asset_info_raw = min_fund_amount.info.to_raw(api)?;
+        let reward_index = reward_asset_infos
+            .iter()
+            .position(|reward_asset_info| reward_asset_info.equal(&asset_info_raw))
+            .ok_or_else(|| ContractError::UnsupportedRewardAsset {
+                asset_info: min_fund_amount.info.to_string(),
+            })?;
+        amounts[reward_index] = min_fund_amount.amount;
+    }
+
+    Ok(amounts)
+}
+
+/// Checks that every reward asset has a minimum fund amount if the funding is permissionless,
+/// so that the distribution can not be spammed with dust fundings
+pub fn assert_min_fund_amounts(api: &dyn Api, config: &Config) -> Result<(), ContractError> {
+    if !config.permissionless_funding {
+        return Ok(());
+    }
+
+    for (reward_index, asset_info) in config.reward_asset_infos.iter().enumerate() {
+        if config
+            .min_fund_amounts
+            .get(reward_index)
+            .cloned()
+            .unwrap_or_default()
+            .is_zero()
+        {
+            return Err(ContractError::MinFundAmountRequired {
+                asset_info: asset_info.to_normal(api)?.to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Drops the lock positions which are expired at `time`
+pub fn release_expired_locks(user_info: &mut UserInfo, time: u64) {
+    user_info.locks.retain(|lock| lock.end_time > time);
+}
+
+/// Maximum number of open lock positions of a user, which bounds the
+/// lock positions iterated on every stake change of the user
+pub const MAX_LOCK_POSITIONS: usize = 10;
+
+/// Adds `lock` to the open lock positions of the user, merged into the position
+/// with the same end time and boost if there is one
+pub fn add_lock(user_info: &mut UserInfo, lock: LockPosition) -> Result<(), ContractError> {
+    if let Some(position) = user_info
+        .locks
+        .iter_mut()
+        .find(|position| position.end_time == lock.end_time && position.boost == lock.boost)
+    {
+        position.amount = position.amount.checked_add(lock.amount)?;
+        return Ok(());
+    }
+
+    if user_info.locks.len() >= MAX_LOCK_POSITIONS {
+        return Err(ContractError::TooManyLocks {
+            max: MAX_LOCK_POSITIONS,
+        });
+    }
+    user_info.locks.push(lock);
+
+    Ok(())
+}
+
+/// Stake amount which is not held by any lock position
+pub fn compute_unlocked_amount(user_info: &UserInfo) -> StdResult<Uint128> {
+    let mut unlocked_amount = user_info.stake_amount;
+    for lock in user_info.locks.iter() {
+        unlocked_amount = unlocked_amount.checked_sub(lock.amount)?;
+    }
+
+    Ok(unlocked_amount)
+}
+
+/// Recomputes the weight of the user with the boosted lock positions
+/// and applies the difference to `total_weight`
+pub fn update_weight(state: &mut State, user_info: &mut UserInfo) -> StdResult<()> {
+    let mut weight = compute_unlocked_amount(user_info)?;
+    for lock in user_info.locks.iter() {
+        weight = weight.checked_add(Uint128::try_from(Uint256::from(lock.amount) * lock.boost)?)?;
+    }
+
+    state.total_weight = state
+        .total_weight
+        .checked_sub(user_info.weight)?
+        .checked_add(weight)?;
+    user_info.weight = weight;
+
+    Ok(())
+}
+
+/// Adds `amount` to the funded amounts of the funder, restarting the cap period if it has passed
+pub fn record_funding(
+    funder_info: &mut FunderInfo,
+    reward_index: usize,
+    amount: Uint128,
+    time: u64,
+) -> Result<(), ContractError> {
+    if funder_info.rewards.len() <= reward_index {
+        funder_info
+            .rewards
+            .resize(reward_index + 1, FunderReward::default());
+    }
+    let funder_reward = &mut funder_info.rewards[reward_index];
+
+    if time >= add_seconds(funder_reward.period_start, funder_reward.cap_period)? {
+        funder_reward.period_start = time;
+        funder_reward.period_funded_amount = Uint128::zero();
+    }
+
+    if let Some(cap_amount) = funder_reward.cap_amount {
+        let remaining = cap_amount
+            .checked_sub(funder_reward.period_funded_amount)
+            .unwrap_or_default();
+        if amount > remaining {
+            return Err(ContractError::FundingCapExceeded {
+                requested: amount,
+                remaining,
+            });
+        }
+    }
+
+    funder_reward.period_funded_amount = funder_reward.period_funded_amount.checked_add(amount)?;
+    funder_reward.funded_amount = funder_reward.funded_amount.checked_add(amount)?;
+
+    Ok(())
+}
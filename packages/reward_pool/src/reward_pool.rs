@@ -1,76 +1,632 @@
-use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
-
-use cosmwasm_std::{Decimal, Uint128};
-use cw20::Cw20ReceiveMsg;
-use terraswap::asset::AssetInfo;
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct InstantiateMsg {
-    pub governance: String,
-    pub funder: String,
-    pub staking_token: String,
-    pub reward_asset_info: AssetInfo,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-#[serde(rename_all = "snake_case")]
-pub enum ExecuteMsg {
-    Receive(Cw20ReceiveMsg),
-    Withdraw {
-        amount: Uint128,
-    },
-    Claim {},
-    Fund {},
-    UpdateConfig {
-        governance: Option<String>,
-        funder: Option<String>,
-    },
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-#[serde(rename_all = "snake_case")]
-pub enum Cw20HookMsg {
-    Deposit {},
-    Fund {},
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-#[serde(rename_all = "snake_case")]
-pub struct MigrateMsg {}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-#[serde(rename_all = "snake_case")]
-pub enum QueryMsg {
-    Config {},
-    State {},
-    UserInfo { user: String },
-}
-
-// We define a custom struct for each query response
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct ConfigResponse {
-    pub governance: String,
-    pub funder: String,
-    pub staking_token: String,
-    pub reward_asset_info: AssetInfo,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
-pub struct State {
-    pub acc_per_share: Decimal,
-    pub total_deposits: Uint128,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
-pub struct UserInfo {
-    pub stake_amount: Uint128,
-    pub pending_amount: Uint128,
-    pub reward_debt: Uint128,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
-pub struct UserInfoResponse {
-    pub stake_amount: Uint128,
-    pub pending_amount: Uint128,
-}
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{
+    to_binary, Binary, CosmosMsg, Decimal, Decimal256, StdResult, Uint128, WasmMsg,
+};
+use cw20::{Cw20Coin, Cw20ReceiveMsg, Expiration, MinterResponse};
+use terraswap::asset::{Asset, AssetInfo};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub governance: String,
+    /// Can pause but not unpause, in addition to governance
+    pub guardian: Option<String>,
+    /// Enabled funders without caps
+    pub funders: Vec<String>,
+    /// cw20 token staked through `Receive` or native denom staked through `Deposit`
+    pub staking_asset_info: AssetInfo,
+    pub reward_asset_infos: Vec<AssetInfo>,
+    pub lock_tiers: Vec<LockTier>,
+    /// Seconds between a withdrawal and the release of the staking token, 0 to release immediately
+    pub unbonding_period: u64,
+    pub forfeit_mode: ForfeitMode,
+    /// Lets anyone fund the reward assets at least `min_fund_amounts`, in addition to the
+    /// enabled funders. Every reward asset needs a non-zero minimum if set
+    pub permissionless_funding: bool,
+    pub min_fund_amounts: Vec<Asset>,
+    /// Answers the cw4 queries, with the stake divided by `tokens_per_weight` as weight
+    pub cw4_enabled: bool,
+    pub tokens_per_weight: Uint128,
+    /// Instantiates a cw20 receipt token which mirrors the stakes
+    pub receipt_token: Option<ReceiptTokenInfo>,
+}
+
+/// cw20 code which is instantiated as the receipt token with the pool as minter.
+/// The code has to send `ReceiptTransferHook` to its minter on every transfer.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReceiptTokenInfo {
+    pub code_id: u64,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+/// Instantiate msg of the receipt token, in the format of cw20-base
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReceiptTokenInstantiateMsg {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub initial_balances: Vec<Cw20Coin>,
+    pub mint: Option<MinterResponse>,
+}
+
+/// Swaps the claimed rewards to `ask_asset_info` through the whitelisted pairs.
+/// `max_slippage` is the max spread of each swap. Fails if the swaps and the claimed
+/// rewards of the ask asset amount to less than `minimum_receive` in total.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimSwap {
+    pub ask_asset_info: AssetInfo,
+    pub max_slippage: Decimal,
+    pub minimum_receive: Uint128,
+}
+
+/// What happens to the pending rewards of an emergency withdrawal
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ForfeitMode {
+    /// Distributed to the remaining stakers
+    Redistribute,
+    /// Kept in the pool until governance sweeps them
+    SetAside,
+}
+
+/// Stake locked for `duration` seconds counts `boost` times in the reward share
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockTier {
+    pub duration: u64,
+    pub boost: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    /// Stakes the attached staking denom, see `Cw20HookMsg::Deposit`
+    Deposit {
+        recipient: Option<String>,
+        lock_duration: Option<u64>,
+    },
+    /// Proceeds are sent to `recipient`, or the sender if not set.
+    /// If the pool has a receipt token, the receipts are sent with `Cw20HookMsg::Withdraw` instead
+    Withdraw {
+        amount: Uint128,
+        recipient: Option<String>,
+    },
+    Claim {
+        recipient: Option<String>,
+        swap: Option<ClaimSwap>,
+    },
+    ClaimUnbonded {},
+    /// Adds the pending rewards to the stake, the staking token has to be a reward asset
+    Compound {},
+    /// Compounds the pending staking token rewards whenever the rewards of the sender are settled
+    SetAutoCompound {
+        enabled: bool,
+    },
+    /// Allows `operator` to claim for the sender, and to withdraw if `can_withdraw`,
+    /// until `expires`. Replaces the previous approval of the operator.
+    /// Pools with a receipt token reject `can_withdraw`
+    Approve {
+        operator: String,
+        can_withdraw: bool,
+        expires: Option<Expiration>,
+    },
+    Revoke {
+        operator: String,
+    },
+    /// Claims the rewards of `user` to the sender, which has to be approved by `user`
+    ClaimFor {
+        user: String,
+    },
+    /// Withdraws the stake of `user` to the sender, which has to be approved by `user` to withdraw.
+    /// Always fails if the pool has a receipt token, the stake is withdrawn with the receipts then
+    WithdrawFor {
+        user: String,
+        amount: Uint128,
+    },
+    /// Removes the whole stake and forfeits the pending rewards, locked stake is unbonded
+    /// until the end of its lock. If the pool has a receipt token, the receipts are sent
+    /// with `Cw20HookMsg::EmergencyWithdraw` instead
+    EmergencyWithdraw {},
+    Fund {},
+    FundStream {
+        start_time: u64,
+        end_time: u64,
+    },
+    UpdateConfig {
+        guardian: Option<String>,
+        unbonding_period: Option<u64>,
+        forfeit_mode: Option<ForfeitMode>,
+        permissionless_funding: Option<bool>,
+        /// Replaces the minimum fund amounts, reward assets which are not listed have no minimum
+        min_fund_amounts: Option<Vec<Asset>>,
+        cw4_enabled: Option<bool>,
+        tokens_per_weight: Option<Uint128>,
+    },
+    /// Nominates a new governance which has to accept within `expires_in` seconds
+    ProposeGovernance {
+        governance: String,
+        expires_in: u64,
+    },
+    AcceptGovernance {},
+    CancelGovernanceProposal {},
+    /// Adds the funder or replaces its settings, funded amounts are kept
+    UpdateFunder {
+        funder: String,
+        enabled: bool,
+        caps: Vec<FundingCap>,
+    },
+    AddRewardAsset {
+        asset_info: AssetInfo,
+    },
+    /// Whitelists the terraswap pair of `asset_infos` for claim swaps
+    AddSwapPair {
+        pair: String,
+        asset_infos: [AssetInfo; 2],
+    },
+    RemoveSwapPair {
+        pair: String,
+    },
+    /// Registers a contract which is notified of every stake change
+    AddHook {
+        addr: String,
+    },
+    RemoveHook {
+        addr: String,
+    },
+    /// Moves the stake of a receipt transfer, only the receipt token can call
+    ReceiptTransferHook(ReceiptTransferHookMsg),
+    SweepForfeitedRewards {
+        recipient: String,
+    },
+    /// Distributes `amount` of the tax reserve of `asset_info` to the stakers,
+    /// e.g. the surplus left by the tax cap
+    DistributeTaxReserve {
+        asset_info: AssetInfo,
+        amount: Uint128,
+    },
+    /// Governance can set any flag, the guardian can only pause
+    SetPaused {
+        deposit: Option<bool>,
+        fund: Option<bool>,
+        claim: Option<bool>,
+        withdraw: Option<bool>,
+    },
+    /// Converts up to `limit` users of a single reward asset pool after `migrate`,
+    /// anyone can call until every user is converted
+    MigrateUsers {
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Credits `recipient`, or the sender if not set.
+    /// `lock_duration` has to be the duration of one of the lock tiers,
+    /// and is only accepted if the sender is credited
+    Deposit {
+        recipient: Option<String>,
+        lock_duration: Option<u64>,
+    },
+    Fund {},
+    FundStream {
+        start_time: u64,
+        end_time: u64,
+    },
+    /// Withdraws the stake of the sent receipts, which the pool burns.
+    /// Only accepted from the receipt token, proceeds are sent to `recipient`, or the sender if not set
+    Withdraw {
+        recipient: Option<String>,
+    },
+    /// Emergency withdrawal with the receipts of the whole stake, only accepted from the receipt token
+    EmergencyWithdraw {},
+}
+
+/// Sent to the hook contracts when the stake of `user` changes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakeChangedHookMsg {
+    pub user: String,
+    pub old_stake: Uint128,
+    pub new_stake: Uint128,
+}
+
+impl StakeChangedHookMsg {
+    /// Serializes the message as `{"stake_changed_hook": {...}}`
+    pub fn into_binary(self) -> StdResult<Binary> {
+        to_binary(&StakeChangedExecuteMsg::StakeChangedHook(self))
+    }
+
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: self.into_binary()?,
+            funds: vec![],
+        }))
+    }
+}
+
+/// Sent by the receipt token when `amount` receipts are moved from `sender` to `recipient`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReceiptTransferHookMsg {
+    pub sender: String,
+    pub recipient: String,
+    pub amount: Uint128,
+}
+
+impl ReceiptTransferHookMsg {
+    /// Serializes the message as `{"receipt_transfer_hook": {...}}`
+    pub fn into_binary(self) -> StdResult<Binary> {
+        to_binary(&ExecuteMsg::ReceiptTransferHook(self))
+    }
+
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: self.into_binary()?,
+            funds: vec![],
+        }))
+    }
+}
+
+/// Execute msg which the hook contracts have to accept
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StakeChangedExecuteMsg {
+    StakeChangedHook(StakeChangedHookMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    State {},
+    UserInfo {
+        user: String,
+    },
+    ClaimableRewards {
+        user: String,
+    },
+    AllUsers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    UserStakeAtHeight {
+        user: String,
+        height: u64,
+    },
+    TotalStakeAtHeight {
+        height: u64,
+    },
+    RewardSchedules {},
+    Solvency {},
+    Locks {
+        user: String,
+    },
+    Unbonds {
+        user: String,
+    },
+    Funders {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    FundHistory {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    Approvals {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    SwapPairs {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    Hooks {},
+    /// cw4 compatible, the weight of a member is its stake amount divided by `tokens_per_weight`.
+    /// The cw4 queries fail unless `cw4_enabled` is set
+    Member {
+        addr: String,
+        at_height: Option<u64>,
+    },
+    /// cw4 compatible, the total weight is the total deposits divided by `tokens_per_weight`
+    TotalWeight {
+        at_height: Option<u64>,
+    },
+    /// cw4 compatible, lists the users with a non-zero weight
+    ListMembers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub governance: String,
+    pub pending_governance: Option<PendingHandover>,
+    pub guardian: Option<String>,
+    pub staking_asset_info: AssetInfo,
+    pub reward_asset_infos: Vec<AssetInfo>,
+    pub lock_tiers: Vec<LockTier>,
+    pub unbonding_period: u64,
+    pub forfeit_mode: ForfeitMode,
+    pub permissionless_funding: bool,
+    /// Reward assets with a minimum fund amount
+    pub min_fund_amounts: Vec<Asset>,
+    pub cw4_enabled: bool,
+    pub tokens_per_weight: Uint128,
+    pub paused: PauseFlags,
+    pub receipt_token: Option<String>,
+}
+
+/// At most `amount` of `asset_info` can be funded by the funder in every `period` seconds
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FundingCap {
+    pub asset_info: AssetInfo,
+    pub period: u64,
+    pub amount: Uint128,
+}
+
+/// `funded_amounts` are the total amounts funded since the funder was added
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FunderResponse {
+    pub funder: String,
+    pub enabled: bool,
+    pub caps: Vec<FundingCap>,
+    pub funded_amounts: Vec<Asset>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct FundersResponse {
+    pub funders: Vec<FunderResponse>,
+}
+
+/// `start_time` and `end_time` are set if the rewards were streamed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FundRecord {
+    pub id: u64,
+    pub funder: String,
+    pub asset: Asset,
+    pub time: u64,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct FundHistoryResponse {
+    pub records: Vec<FundRecord>,
+}
+
+/// Expired approvals are listed until the owner revokes them
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Approval {
+    pub operator: String,
+    pub can_withdraw: bool,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ApprovalsResponse {
+    pub approvals: Vec<Approval>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapPair {
+    pub pair: String,
+    pub asset_infos: [AssetInfo; 2],
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct SwapPairsResponse {
+    pub pairs: Vec<SwapPair>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HooksResponse {
+    pub hooks: Vec<String>,
+}
+
+/// `weight` is `None` if `addr` has no stake
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MemberResponse {
+    pub weight: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TotalWeightResponse {
+    pub weight: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Member {
+    pub addr: String,
+    pub weight: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MemberListResponse {
+    pub members: Vec<Member>,
+}
+
+/// Address nominated by governance which can accept the role until `expires_at`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingHandover {
+    pub address: String,
+    pub expires_at: u64,
+}
+
+/// `withdraw` covers withdrawals, unbond claims and emergency withdrawals
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PauseFlags {
+    pub deposit: bool,
+    pub fund: bool,
+    pub claim: bool,
+    pub withdraw: bool,
+}
+
+/// `total_unbonding` is the withdrawn staking token amount which is not released yet.
+/// `total_weight` is the sum of the user weights, in which locked stake is boosted,
+/// and is used as the denominator of the reward distribution.
+/// `rewards` is indexed in the same order as the reward asset infos of the config
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct State {
+    pub total_deposits: Uint128,
+    pub total_weight: Uint128,
+    pub total_unbonding: Uint128,
+    pub staker_count: u64,
+    pub rewards: Vec<RewardState>,
+}
+
+/// `undistributed_amount` holds rewards funded while there were no deposits
+/// and the rounding remainder of previous distributions,
+/// which are added to `acc_per_share` on the next distribution.
+/// `distributed_amount` is the total amount added to `acc_per_share`
+/// and `claimed_amount` is the total amount paid out to users.
+/// `forfeited_amount` holds the rewards set aside by emergency withdrawals.
+/// `tax_reserve` holds the tax reserved on funding, which pays the tax of native payouts.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct RewardState {
+    pub acc_per_share: Decimal256,
+    pub undistributed_amount: Uint128,
+    pub distributed_amount: Uint128,
+    pub claimed_amount: Uint128,
+    pub forfeited_amount: Uint128,
+    pub tax_reserve: Uint128,
+}
+
+/// Reward amount which is emitted linearly between `start_time` and `end_time`
+/// `reward_index` is the index of the reward asset in the config
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct RewardSchedule {
+    pub reward_index: u32,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub amount: Uint128,
+    pub released_amount: Uint128,
+}
+
+impl RewardSchedule {
+    /// Amount which should have been emitted at `time`
+    pub fn vested_amount(&self, time: u64) -> Uint128 {
+        if time <= self.start_time {
+            Uint128::zero()
+        } else if time >= self.end_time {
+            self.amount
+        } else {
+            self.amount
+                .multiply_ratio(time - self.start_time, self.end_time - self.start_time)
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct RewardSchedulesResponse {
+    pub schedules: Vec<RewardSchedule>,
+}
+
+/// `owed_amount` is an upper bound of what the pool has to pay out in the asset (unclaimed
+/// and not yet distributed rewards, the tax reserve, and the deposits if the asset is the
+/// staking token). `tax_reserve` is the part reserved for the tax of native payouts.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardSolvency {
+    pub asset_info: AssetInfo,
+    pub balance: Uint128,
+    pub owed_amount: Uint128,
+    pub tax_reserve: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct SolvencyResponse {
+    pub rewards: Vec<RewardSolvency>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct UserInfo {
+    pub stake_amount: Uint128,
+    pub weight: Uint128,
+    pub locks: Vec<LockPosition>,
+    pub rewards: Vec<UserReward>,
+    pub auto_compound: bool,
+}
+
+/// Part of the stake which can not be withdrawn before `end_time`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockPosition {
+    pub amount: Uint128,
+    pub boost: Decimal256,
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct UserReward {
+    pub pending_amount: Uint128,
+    pub reward_debt: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserInfoResponse {
+    pub stake_amount: Uint128,
+    pub pending_rewards: Vec<Asset>,
+    pub auto_compound: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserResponse {
+    pub user: String,
+    pub stake_amount: Uint128,
+    pub pending_rewards: Vec<Asset>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllUsersResponse {
+    pub users: Vec<UserResponse>,
+}
+
+/// Stake at the start of block `height`, changes made in that block are not included
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakeAtHeightResponse {
+    pub stake_amount: Uint128,
+    pub height: u64,
+}
+
+/// Reward asset which a claim delivers. `amount` is received in full and `tax_amount`
+/// is the tax the pool pays on top of the transfer out of its tax reserve.
+/// If the reserve is short, the tax is deducted from `amount` instead.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimableReward {
+    pub asset_info: AssetInfo,
+    pub amount: Uint128,
+    pub tax_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimableRewardsResponse {
+    pub rewards: Vec<ClaimableReward>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct LocksResponse {
+    pub locks: Vec<LockPosition>,
+}
+
+/// Withdrawn staking token which can be claimed from `release_time` and is sent to `recipient`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Unbond {
+    pub amount: Uint128,
+    pub release_time: u64,
+    pub recipient: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct UnbondsResponse {
+    pub unbonds: Vec<Unbond>,
+}